@@ -1,37 +1,235 @@
 contract;
 
+use core::ops::Eq;
+
 use std::{
+    address::Address,
     auth::{
         AuthError,
         msg_sender,
     },
+    block::height,
     call_frames::msg_asset_id,
-    constants::BASE_ASSET_ID,
+    constants::{
+        BASE_ASSET_ID,
+        ZERO_B256,
+    },
     context::{
         msg_amount,
         this_balance,
     },
+    contract_id::ContractId,
+    hash::sha256,
     identity::Identity,
+    logging::log,
     option::Option,
     storage::StorageMap,
     storage::StorageVec,
     token::transfer,
+    vec::Vec,
 };
 
+pub enum Codec {
+    // base58btc dag-pb CID
+    CidV0: (),
+    // CID with the given multicodec content type
+    CidV1: u64,
+    // Arweave transaction id
+    Arweave: (),
+}
+
+pub struct Metadata {
+    codec: Codec,
+    // sha256 digest of the encrypted content
+    digest: b256,
+}
+
+impl Eq for Codec {
+    fn eq(self, other: Self) -> bool {
+        match self {
+            Codec::CidV0 => match other {
+                Codec::CidV0 => true,
+                _ => false,
+            },
+            Codec::CidV1(content_codec) => match other {
+                Codec::CidV1(other_content_codec) => content_codec == other_content_codec,
+                _ => false,
+            },
+            Codec::Arweave => match other {
+                Codec::Arweave => true,
+                _ => false,
+            },
+        }
+    }
+}
+
+impl Eq for Metadata {
+    fn eq(self, other: Self) -> bool {
+        self.codec == other.codec && self.digest == other.digest
+    }
+}
+
+pub struct MetadataVersion {
+    metadata: Metadata,
+    // block height the version was published at
+    block_height: u64,
+    // optional content id describing what changed
+    changelog: Option<Metadata>,
+}
+
+pub struct KeyDelivery {
+    // the buyer's public key the content key was encrypted to
+    encryption_key: b256,
+    // the encrypted content key
+    encrypted_key: [b256; 3],
+    // block height the key was delivered at
+    block_height: u64,
+}
+
+pub struct BuyerPage {
+    buyers: [Identity; 5],
+    // # of buyers filled in, starting at buyers[0]
+    count: u64,
+}
+
+pub struct CreatorProfile {
+    // hash of the creator's display name
+    display_name_hash: b256,
+    // content id of the full profile
+    profile: Metadata,
+}
+
+pub struct Creator {
+    profile: Option<CreatorProfile>,
+    // # of projects listed
+    project_count: u64,
+    // # of projects sold
+    sales: u64,
+    // total amount paid to the creator
+    earnings: u64,
+}
+
+pub struct ProjectPage {
+    projects: [Project; 5],
+    // # of projects filled in, starting at projects[0]
+    count: u64,
+}
+
+pub struct Purchase {
+    purchase_id: u64,
+    buyer: Identity,
+    project_id: u64,
+    asset_id: ContractId,
+    // amount paid for the project
+    amount: u64,
+    // part of the amount kept by the contract
+    commission: u64,
+    // block height the project was bought at
+    block_height: u64,
+}
+
+pub struct DutchAuction {
+    start_price: u64,
+    floor_price: u64,
+    // the price starts falling after this block
+    start_block: u64,
+    // the price stays at floor_price from this block on
+    end_block: u64,
+}
+
+pub struct SealedAuction {
+    // bids can be committed before this block
+    commit_end_block: u64,
+    // bids can be revealed from commit_end_block until this block
+    reveal_end_block: u64,
+    // the project's price when the auction started, a lower highest bid doesn't win
+    reserve_price: u64,
+    highest_bid: u64,
+    highest_bidder: Option<Identity>,
+    settled: bool,
+}
+
+pub struct SealedBid {
+    // sha256 of the bid amount and a secret salt
+    commitment: b256,
+    // amount escrowed with the commitment
+    deposit: u64,
+    revealed_bid: Option<u64>,
+    withdrawn: bool,
+}
+
+pub struct Presale {
+    // # of buyers needed, 0 if there is no buyer goal
+    goal_buyers: u64,
+    // amount needed, 0 if there is no revenue goal
+    goal_revenue: u64,
+    // a goal has to be met by this block
+    deadline_block: u64,
+    // amount escrowed until a goal is met, less any refunds
+    raised: u64,
+    // part of raised kept as commission
+    commission: u64,
+    goal_met: bool,
+    released: bool,
+}
+
+pub struct MarketplaceStats {
+    project_count: u64,
+    purchase_count: u64,
+    review_count: u64,
+    // # of different Identities that bought a project
+    buyer_count: u64,
+    // # of different Identities that listed a project
+    creator_count: u64,
+    // total amount of the base asset paid for projects
+    volume: u64,
+}
+
 pub struct Project {
     project_id: u64,
     price: u64,
     max_buyers: u64,
     buyer_count: u64,
     owner_address: Identity,
-    // encrypted IPFS CID
-    metadata: str[59],
+    // encrypted content id
+    metadata: Metadata,
+    // category id, 0 if uncategorized
+    category: u64,
+    // tag ids, 0 for an empty slot
+    tags: [u64; 3],
+    // total paid for the project, not counting resales
+    total_revenue: u64,
+    // part of total_revenue kept by the contract
+    total_commission: u64,
 }
 
 impl Project {
+    fn empty() -> Self {
+        Self {
+            project_id: 0,
+            price: 0,
+            max_buyers: 0,
+            buyer_count: 0,
+            owner_address: Identity::Address(Address::from(ZERO_B256)),
+            metadata: Metadata {
+                codec: Codec::CidV0,
+                digest: ZERO_B256,
+            },
+            category: 0,
+            tags: [0; 3],
+            total_revenue: 0,
+            total_commission: 0,
+        }
+    }
+
     fn update_buyer_count(ref mut self) {
         self.buyer_count = self.buyer_count + 1;
     }
+
+    fn add_revenue(ref mut self, amount: u64, commission: u64) {
+        self.total_revenue = self.total_revenue + amount;
+        self.total_commission = self.total_commission + commission;
+    }
 }
 
 pub enum InvalidError {
@@ -44,6 +242,83 @@ pub enum InvalidError {
     OnlyOwner: (),
     OwnerNotInitialized: (),
     OwnerAlreadyInitialized: (),
+    InvalidReferrer: (),
+    InvalidReferralRate: (),
+    AlreadyBought: (),
+    NotLicenseHolder: (),
+    NotListedForResale: (),
+    InvalidRoyaltyRate: (),
+    VersionNotFound: (),
+    KeyNotRegistered: (),
+    CantFetchKey: (),
+    BuyerNotFound: (),
+    InvalidCategory: (),
+    ProjectNotFoundInCategory: (),
+    PageTooLarge: (),
+    ProjectNotFound: (),
+    IndexOutOfBounds: (),
+    PurchaseNotFound: (),
+    InvalidAuction: (),
+    NotOneOfOne: (),
+    AuctionActive: (),
+    NotCommitPhase: (),
+    NotRevealPhase: (),
+    AuctionNotEnded: (),
+    AuctionNotSettled: (),
+    AlreadyCommitted: (),
+    BidNotFound: (),
+    InvalidReveal: (),
+    AlreadyWithdrawn: (),
+    InvalidPresale: (),
+    PresaleActive: (),
+    PresaleNotFailed: (),
+    NotPresalePayer: (),
+    InvalidTip: (),
+    InvalidBundle: (),
+    BundleNotFound: (),
+    ProjectHidden: (),
+    ProjectNotHidden: (),
+    ReviewNotFound: (),
+    Blocked: (),
+}
+
+pub struct GiftEvent {
+    project_id: u64,
+    gifter: Identity,
+    recipient: Identity,
+}
+
+pub struct ProjectHiddenEvent {
+    project_id: u64,
+    reason_code: u64,
+}
+
+pub struct ProjectUnhiddenEvent {
+    project_id: u64,
+}
+
+pub struct ReviewRemovedEvent {
+    review_id: u64,
+    project_id: u64,
+    reviewer: Identity,
+}
+
+pub struct Bundle {
+    bundle_id: u64,
+    owner_address: Identity,
+    // ids of the projects in the bundle
+    projects: Vector,
+    // price of the whole bundle, split evenly between its projects
+    price: u64,
+}
+
+pub struct TipEvent {
+    project_id: u64,
+    tipper: Identity,
+    creator: Identity,
+    amount: u64,
+    // part of the tip kept by the contract
+    commission: u64,
 }
 
 struct Vector {
@@ -59,10 +334,19 @@ impl Vector {
         }
     }
 
-    fn get(ref mut self, ix: u64) -> u64 {
+    fn get(self, ix: u64) -> u64 {
+        require(ix < self.current_ix, InvalidError::IndexOutOfBounds);
         self.inner[ix]
     }
 
+    fn try_get(self, ix: u64) -> Option<u64> {
+        if ix < self.current_ix {
+            Option::Some(self.inner[ix])
+        } else {
+            Option::None
+        }
+    }
+
     fn push(ref mut self, val: u64) {
         // only update if array not full
         match self.current_ix {
@@ -81,6 +365,32 @@ impl Vector {
         }
         self.current_ix = self.current_ix + 1;
     }
+
+    fn remove(ref mut self, val: u64) {
+        // rebuild the array without the removed value
+        let mut new_vector = Vector::new();
+        let mut i = 0;
+        while i < self.current_ix {
+            let item = self.inner[i];
+            if item != val {
+                new_vector.push(item);
+            }
+            i += 1;
+        }
+        self.inner = new_vector.inner;
+        self.current_ix = new_vector.current_ix;
+    }
+
+    fn contains(self, val: u64) -> bool {
+        let mut i = 0;
+        while i < self.current_ix {
+            if self.inner[i] == val {
+                return true;
+            }
+            i += 1;
+        }
+        false
+    }
 }
 
 storage {
@@ -90,6 +400,52 @@ storage {
     ratings_map: StorageMap<u64, Vector> = StorageMap {},
     // map of buyer Identity => a Vector of project ids they bought
     buyers: StorageMap<Identity, Vector> = StorageMap {},
+    // map of (buyer Identity, project id) => purchase id
+    purchases: StorageMap<(Identity, u64), Option<u64>> = StorageMap {},
+    // map of purchase id => Purchase
+    purchase_receipts: StorageMap<u64, Purchase> = StorageMap {},
+    // total # of purchases made
+    purchase_count: u64 = 0,
+    // total # of purchases undone by a refund
+    refunded_purchase_count: u64 = 0,
+    // total # of different buyers
+    unique_buyer_count: u64 = 0,
+    // total # of different creators
+    unique_creator_count: u64 = 0,
+    // map of buyer Identity => true once they've bought a project
+    known_buyers: StorageMap<Identity, bool> = StorageMap {},
+    // map of buyer Identity => # of purchases they made that weren't refunded
+    buyer_purchase_count: StorageMap<Identity, u64> = StorageMap {},
+    // map of asset id => total amount paid for projects
+    volume: StorageMap<ContractId, u64> = StorageMap {},
+    // map of project id => DutchAuction, if the project is priced by auction
+    dutch_auctions: StorageMap<u64, Option<DutchAuction>> = StorageMap {},
+    // map of project id => SealedAuction, if the project is sold by sealed bids
+    sealed_auctions: StorageMap<u64, Option<SealedAuction>> = StorageMap {},
+    // map of (project id, bidder Identity) => SealedBid
+    sealed_bids: StorageMap<(u64, Identity), Option<SealedBid>> = StorageMap {},
+    // total base asset held for buyers, which the owner can't withdraw
+    escrow_balance: u64 = 0,
+    // map of project id => Presale, if the project was pre-sold
+    presales: StorageMap<u64, Option<Presale>> = StorageMap {},
+    // map of (project id, buyer Identity) => Identity that paid, if bought during a pre-sale
+    presale_payers: StorageMap<(u64, Identity), Option<Identity>> = StorageMap {},
+    // map of project id => total amount tipped to the creator
+    project_tips: StorageMap<u64, u64> = StorageMap {},
+    // if true, the contract keeps the usual commission from tips
+    tip_commission: bool = false,
+    // map of bundle id => Bundle
+    bundles: StorageMap<u64, Option<Bundle>> = StorageMap {},
+    // total # of bundles made
+    bundle_count: u64 = 0,
+    // map of project id => reason code, if the contract owner hid the project
+    hidden_projects: StorageMap<u64, Option<u64>> = StorageMap {},
+    // map of review index => true if the contract owner removed the review
+    removed_reviews: StorageMap<u64, bool> = StorageMap {},
+    // total # of removed reviews
+    removed_review_count: u64 = 0,
+    // map of Identity => true if the contract owner blocked it
+    blocked: StorageMap<Identity, bool> = StorageMap {},
     creators: StorageMap<Identity, Vector> = StorageMap {},
     // map of project id => Project
     project_listings: StorageMap<u64, Project> = StorageMap {},
@@ -97,21 +453,592 @@ storage {
     project_count: u64 = 0,
     // owner of the contract
     owner: Option<Identity> = Option::None,
+    // map of project id => % of the creator's share paid to referrers (0-100)
+    referral_rates: StorageMap<u64, u64> = StorageMap {},
+    // map of referrer Identity => total # of sales they referred
+    referral_sales: StorageMap<Identity, u64> = StorageMap {},
+    // map of referrer Identity => total amount earned from referrals
+    referral_earnings: StorageMap<Identity, u64> = StorageMap {},
+    // map of project id => % of each resale paid to the creator (0-100)
+    royalty_rates: StorageMap<u64, u64> = StorageMap {},
+    // map of (project id, seller Identity) => resale price
+    resale_listings: StorageMap<(u64, Identity), Option<u64>> = StorageMap {},
+    // map of (project id, version #) => MetadataVersion
+    metadata_versions: StorageMap<(u64, u64), MetadataVersion> = StorageMap {},
+    // map of project id => # of metadata versions
+    metadata_version_count: StorageMap<u64, u64> = StorageMap {},
+    // map of (project id, buyer Identity) => metadata version # they bought
+    purchased_versions: StorageMap<(u64, Identity), Option<u64>> = StorageMap {},
+    // map of buyer Identity => public key content keys are encrypted to
+    encryption_keys: StorageMap<Identity, Option<b256>> = StorageMap {},
+    // map of (project id, buyer Identity) => encrypted content key
+    delivered_keys: StorageMap<(u64, Identity), Option<KeyDelivery>> = StorageMap {},
+    // map of (project id, index) => buyer Identity
+    project_buyers: StorageMap<(u64, u64), Identity> = StorageMap {},
+    // map of (project id, buyer Identity) => index in project_buyers
+    project_buyers_ix: StorageMap<(u64, Identity), u64> = StorageMap {},
+    // map of creator Identity => CreatorProfile
+    creator_profiles: StorageMap<Identity, Option<CreatorProfile>> = StorageMap {},
+    // map of creator Identity => total # of projects sold
+    creator_sales: StorageMap<Identity, u64> = StorageMap {},
+    // map of creator Identity => total amount paid to them, including royalties
+    creator_earnings: StorageMap<Identity, u64> = StorageMap {},
+    // map of category id => hash of the category name, ids start at 1
+    categories: StorageMap<u64, b256> = StorageMap {},
+    // total # of categories
+    category_count: u64 = 0,
+    // map of (category id, index) => project id
+    category_projects: StorageMap<(u64, u64), u64> = StorageMap {},
+    // map of category id => # of projects in the category
+    category_project_count: StorageMap<u64, u64> = StorageMap {},
+    // map of project id => index in category_projects
+    category_projects_ix: StorageMap<u64, u64> = StorageMap {},
+}
+
+// max # of items returned by a paginated getter
+const PAGE_SIZE: u64 = 5;
+
+// get a listed Project, reverting if the project ID doesn't exist
+#[storage(read)]
+fn get_existing_project(project_id: u64) -> Project {
+    require(project_id < storage.project_count, InvalidError::ProjectNotFound);
+    storage.project_listings.get(project_id)
+}
+
+// check if the given Identity has bought the given project_id
+#[storage(read)]
+fn has_bought(project_id: u64, wallet: Identity) -> bool {
+    storage.purchases.get((wallet, project_id)).is_some()
+}
+
+// revert if the contract owner blocked the given Identity
+#[storage(read)]
+fn require_not_blocked(wallet: Identity) {
+    require(!storage.blocked.get(wallet), InvalidError::Blocked);
+}
+
+// check if the contract owner hid a project
+#[storage(read)]
+fn is_hidden(project_id: u64) -> bool {
+    storage.hidden_projects.get(project_id).is_some()
+}
+
+// check if a pre-sale missed its goal by the deadline
+fn presale_failed(presale: Presale) -> bool {
+    !presale.goal_met && height() > presale.deadline_block
+}
+
+// check if a project is in a pre-sale that is still taking payments
+#[storage(read)]
+fn presale_active(project_id: u64) -> bool {
+    let presale = storage.presales.get(project_id);
+    presale.is_some() && !presale.unwrap().goal_met && height() <= presale.unwrap().deadline_block
+}
+
+// check if a project has a pre-sale, unless it failed and every payment was refunded
+#[storage(read)]
+fn has_open_presale(project_id: u64) -> bool {
+    let presale = storage.presales.get(project_id);
+    presale.is_some() && !(presale_failed(presale.unwrap()) && presale.unwrap().raised == 0)
+}
+
+// check if the given Identity has bought the given project_id and can access it,
+// which pre-sale buyers can only do once the goal is met
+#[storage(read)]
+fn has_access(project_id: u64, wallet: Identity) -> bool {
+    if !has_bought(project_id, wallet) {
+        return false;
+    }
+    storage.presale_payers.get((project_id, wallet)).is_none() || storage.presales.get(project_id).unwrap().goal_met
+}
+
+// pay the seller their share of a sale, or escrow the payment during a pre-sale
+#[storage(read, write)]
+fn pay_seller(project: Project, asset_id: ContractId, amount: u64, commission: u64) {
+    if presale_active(project.project_id) {
+        let mut presale = storage.presales.get(project.project_id).unwrap();
+        presale.raised = presale.raised + amount;
+        presale.commission = presale.commission + commission;
+        let buyers_met = presale.goal_buyers > 0 && project.buyer_count >= presale.goal_buyers;
+        let revenue_met = presale.goal_revenue > 0 && presale.raised >= presale.goal_revenue;
+        presale.goal_met = buyers_met || revenue_met;
+
+        storage.presales.insert(project.project_id, Option::Some(presale));
+        storage.escrow_balance = storage.escrow_balance + amount;
+    } else if amount > 0 {
+        // send the payout minus commission to the seller
+        transfer(amount - commission, asset_id, project.owner_address);
+    }
+}
+
+// get the part of a bundle's price paid for the project at the given index,
+// with any remainder of the split paid for the last project
+fn get_bundle_share(bundle: Bundle, ix: u64) -> u64 {
+    let count = bundle.projects.current_ix;
+    let share = bundle.price / count;
+    if ix == count - 1 {
+        bundle.price - share * (count - 1)
+    } else {
+        share
+    }
+}
+
+// add a buyer to a project and return the updated Project
+#[storage(read, write)]
+fn add_buyer(
+    project_id: u64,
+    buyer: Identity,
+    asset_id: ContractId,
+    amount: u64,
+    commission: u64,
+) -> Project {
+    // each Identity can only buy a project once
+    require(!has_bought(project_id, buyer), InvalidError::AlreadyBought);
+    require(!is_hidden(project_id), InvalidError::ProjectHidden);
+    require_not_blocked(buyer);
+
+    // pre-sale buyers pay into escrow and can be refunded if the goal is missed
+    if presale_active(project_id) {
+        storage.presale_payers.insert((project_id, buyer), Option::Some(buyer));
+    }
+
+    let mut project: Project = get_existing_project(project_id);
+    // a blocked creator's projects can't be sold
+    require_not_blocked(project.owner_address);
+
+    if (project.max_buyers > 0) {
+        // require buyer_count to be less than the max_buyers limit
+        require(project.max_buyers > project.buyer_count, InvalidError::MaxBuyers);
+    }
+
+    // add buyer to the project's buyer list
+    storage.project_buyers.insert((project_id, project.buyer_count), buyer);
+    storage.project_buyers_ix.insert((project_id, buyer), project.buyer_count);
+
+    // add 1 to the buyer count
+    project.update_buyer_count();
+    project.add_revenue(amount, commission);
+    // update project_listings
+    storage.project_listings.insert(project_id, project);
+    storage.creator_sales.insert(project.owner_address, storage.creator_sales.get(project.owner_address) + 1);
+    // escrowed pre-sale payments are added to the creator's earnings once released
+    if !presale_active(project_id) {
+        add_creator_earnings(project.owner_address, amount - commission);
+    }
+
+    let mut existing: Vector = storage.buyers.get(buyer);
+
+    // add buyer to buyer list
+    existing.push(project_id);
+    storage.buyers.insert(buyer, existing);
+    record_purchase(project_id, buyer, asset_id, amount, commission);
+
+    // record the latest metadata version as the one bought
+    let version = storage.metadata_version_count.get(project_id) - 1;
+    storage.purchased_versions.insert((project_id, buyer), Option::Some(version));
+
+    project
+}
+
+// undo a buyer's escrowed pre-sale purchase of a project and return its Purchase receipt
+#[storage(read, write)]
+fn remove_buyer(project_id: u64, buyer: Identity) -> Purchase {
+    let purchase = storage.purchase_receipts.get(storage.purchases.get((buyer, project_id)).unwrap());
+    storage.purchases.insert((buyer, project_id), Option::None);
+    storage.purchased_versions.insert((project_id, buyer), Option::None);
+    storage.refunded_purchase_count = storage.refunded_purchase_count + 1;
+
+    let mut existing: Vector = storage.buyers.get(buyer);
+    existing.remove(project_id);
+    storage.buyers.insert(buyer, existing);
+
+    let mut project: Project = get_existing_project(project_id);
+
+    // the last buyer takes the removed buyer's place in the project's buyer list
+    let index = storage.project_buyers_ix.get((project_id, buyer));
+    let last_buyer = storage.project_buyers.get((project_id, project.buyer_count - 1));
+    storage.project_buyers.insert((project_id, index), last_buyer);
+    storage.project_buyers_ix.insert((project_id, last_buyer), index);
+
+    // take the sale out of the sales totals
+    project.buyer_count = project.buyer_count - 1;
+    project.total_revenue = project.total_revenue - purchase.amount;
+    project.total_commission = project.total_commission - purchase.commission;
+    storage.project_listings.insert(project_id, project);
+    storage.creator_sales.insert(project.owner_address, storage.creator_sales.get(project.owner_address) - 1);
+    storage.volume.insert(purchase.asset_id, storage.volume.get(purchase.asset_id) - purchase.amount);
+
+    // a buyer left with no purchases is no longer counted as a buyer
+    let purchase_count = storage.buyer_purchase_count.get(buyer) - 1;
+    storage.buyer_purchase_count.insert(buyer, purchase_count);
+    if purchase_count == 0 {
+        storage.known_buyers.insert(buyer, false);
+        storage.unique_buyer_count = storage.unique_buyer_count - 1;
+    }
+
+    purchase
+}
+
+// add to the lifetime earnings of a creator
+#[storage(read, write)]
+fn add_creator_earnings(creator: Identity, amount: u64) {
+    storage.creator_earnings.insert(creator, storage.creator_earnings.get(creator) + amount);
+}
+
+// add a Purchase receipt and make it the buyer's purchase of the project
+#[storage(read, write)]
+fn record_purchase(
+    project_id: u64,
+    buyer: Identity,
+    asset_id: ContractId,
+    amount: u64,
+    commission: u64,
+) {
+    let purchase_id = storage.purchase_count;
+    storage.purchase_receipts.insert(purchase_id, Purchase {
+        purchase_id: purchase_id,
+        buyer: buyer,
+        project_id: project_id,
+        asset_id: asset_id,
+        amount: amount,
+        commission: commission,
+        block_height: height(),
+    });
+    storage.purchases.insert((buyer, project_id), Option::Some(purchase_id));
+    storage.purchase_count = purchase_id + 1;
+
+    storage.volume.insert(asset_id, storage.volume.get(asset_id) + amount);
+    storage.buyer_purchase_count.insert(buyer, storage.buyer_purchase_count.get(buyer) + 1);
+    if !storage.known_buyers.get(buyer) {
+        storage.known_buyers.insert(buyer, true);
+        storage.unique_buyer_count = storage.unique_buyer_count + 1;
+    }
+}
+
+// add a new metadata version to a project
+#[storage(read, write)]
+fn add_metadata_version(project_id: u64, metadata: Metadata, changelog: Option<Metadata>) {
+    let version = storage.metadata_version_count.get(project_id);
+    storage.metadata_versions.insert((project_id, version), MetadataVersion {
+        metadata: metadata,
+        block_height: height(),
+        changelog: changelog,
+    });
+    storage.metadata_version_count.insert(project_id, version + 1);
+}
+
+// move a bought project from one buyer's list to another's
+#[storage(read, write)]
+fn move_license(
+    project_id: u64,
+    from: Identity,
+    to: Identity,
+    asset_id: ContractId,
+    amount: u64,
+) {
+    require(!has_bought(project_id, to), InvalidError::AlreadyBought);
+
+    let mut existing: Vector = storage.buyers.get(from);
+    existing.remove(project_id);
+    storage.buyers.insert(from, existing);
+
+    let mut existing: Vector = storage.buyers.get(to);
+    existing.push(project_id);
+    storage.buyers.insert(to, existing);
+
+    storage.purchases.insert((from, project_id), Option::None);
+    record_purchase(project_id, to, asset_id, amount, get_commission(amount));
+
+    // the new holder takes the seller's place in the project's buyer list
+    let index = storage.project_buyers_ix.get((project_id, from));
+    storage.project_buyers.insert((project_id, index), to);
+    storage.project_buyers_ix.insert((project_id, to), index);
+
+    // the version bought moves with the license
+    storage.purchased_versions.insert((project_id, to), storage.purchased_versions.get((project_id, from)));
+    storage.purchased_versions.insert((project_id, from), Option::None);
+}
+
+// add a project to a category's project list
+#[storage(read, write)]
+fn add_to_category(project_id: u64, category: u64) {
+    require(category <= storage.category_count, InvalidError::InvalidCategory);
+    if category == 0 {
+        return;
+    }
+
+    let index = storage.category_project_count.get(category);
+    storage.category_projects.insert((category, index), project_id);
+    storage.category_projects_ix.insert(project_id, index);
+    storage.category_project_count.insert(category, index + 1);
+}
+
+// remove a project from a category's project list
+#[storage(read, write)]
+fn remove_from_category(project_id: u64, category: u64) {
+    if category == 0 {
+        return;
+    }
+
+    // move the last project into the removed project's place
+    let index = storage.category_projects_ix.get(project_id);
+    let last_index = storage.category_project_count.get(category) - 1;
+    let last_project_id = storage.category_projects.get((category, last_index));
+    storage.category_projects.insert((category, index), last_project_id);
+    storage.category_projects_ix.insert(last_project_id, index);
+    storage.category_project_count.insert(category, last_index);
+}
+
+// clamp a requested page size to PAGE_SIZE
+fn page_count(count: u64) -> u64 {
+    if count > PAGE_SIZE {
+        PAGE_SIZE
+    } else {
+        count
+    }
+}
+
+// get up to PAGE_SIZE ids from a Vector, starting at the given index
+fn slice(ids: Vector, start: u64, count: u64) -> Vector {
+    let count = page_count(count);
+    let mut page = Vector::new();
+    let mut i = start;
+    while i < ids.current_ix && i - start < count {
+        page.push(ids.inner[i]);
+        i += 1;
+    }
+    page
+}
+
+// get the buyer for an index in a page of project buyers, or the zero Identity past the end
+#[storage(read)]
+fn get_page_buyer(project_id: u64, start: u64, count: u64, ix: u64) -> Identity {
+    if ix < count {
+        storage.project_buyers.get((project_id, start + ix))
+    } else {
+        Identity::Address(Address::from(ZERO_B256))
+    }
+}
+
+// get the Project for an id in a page, or an empty Project past the end
+#[storage(read)]
+fn get_page_project(ids: Vector, ix: u64) -> Project {
+    if ix < ids.current_ix {
+        get_existing_project(ids.inner[ix])
+    } else {
+        Project::empty()
+    }
+}
+
+// get the Projects for a Vector of ids
+#[storage(read)]
+fn get_project_page(ids: Vector) -> ProjectPage {
+    ProjectPage {
+        projects: [
+            get_page_project(ids, 0),
+            get_page_project(ids, 1),
+            get_page_project(ids, 2),
+            get_page_project(ids, 3),
+            get_page_project(ids, 4),
+        ],
+        count: ids.current_ix,
+    }
+}
+
+// only allow the contract owner
+#[storage(read)]
+fn require_owner() {
+    let owner = storage.owner;
+    // make sure the owner has been initialized
+    require(owner.is_some(), InvalidError::OwnerNotInitialized);
+    let sender: Result<Identity, AuthError> = msg_sender();
+    // require the sender to be the owner
+    require(sender.unwrap() == owner.unwrap(), InvalidError::OnlyOwner);
+}
+
+// get the price of a project, falling linearly over a Dutch auction
+#[storage(read)]
+fn current_price(project_id: u64) -> u64 {
+    let project: Project = get_existing_project(project_id);
+
+    // projects in a sealed-bid auction can only be won
+    let sealed_auction = storage.sealed_auctions.get(project_id);
+    require(sealed_auction.is_none() || sealed_auction.unwrap().settled, InvalidError::AuctionActive);
+    let auction = storage.dutch_auctions.get(project_id);
+    if auction.is_none() {
+        return project.price;
+    }
+
+    let auction = auction.unwrap();
+    let block = height();
+    if block <= auction.start_block {
+        return auction.start_price;
+    }
+    if block >= auction.end_block {
+        return auction.floor_price;
+    }
+
+    let elapsed = block - auction.start_block;
+    let duration = auction.end_block - auction.start_block;
+    auction.start_price - (auction.start_price - auction.floor_price) * elapsed / duration
+}
+
+// get the amount charged for a project: the full payment at a fixed price,
+// or the current auction price with the rest refunded
+#[storage(read)]
+fn get_charge(project_id: u64, amount: u64) -> u64 {
+    let price = current_price(project_id);
+    // require payment
+    require(amount >= price, InvalidError::NotEnoughTokens);
+
+    if storage.dutch_auctions.get(project_id).is_some() {
+        price
+    } else {
+        amount
+    }
+}
+
+// get the commission the contract keeps from a payment
+fn get_commission(amount: u64) -> u64 {
+    // only charge commission if price is more than 1_000
+    if amount > 1_000 {
+        // for every 100 coins, the contract keeps 5
+        amount / 20
+    } else {
+        0
+    }
 }
 
 abi WebGum {
     // list a new project for sale
     #[storage(read, write)]
-    fn list_project(price: u64, max_buyers: u64, metadata: str[59]) -> Project;
+    fn list_project(
+        price: u64,
+        max_buyers: u64,
+        metadata: Metadata,
+        category: u64,
+        tags: [u64; 3],
+    ) -> Project;
 
     // update an existing project for sale
     #[storage(read, write)]
-    fn update_project(project_id: u64, price: u64, max_buyers: u64, metadata: str[59]) -> Project;
+    fn update_project(
+        project_id: u64,
+        price: u64,
+        max_buyers: u64,
+        metadata: Metadata,
+        changelog: Option<Metadata>,
+        category: u64,
+        tags: [u64; 3],
+    ) -> Project;
 
     // buy a listed project
     #[storage(read, write)]
     fn buy_project(project_id: u64);
 
+    // buy a listed project and pay a referral commission to the referrer
+    #[storage(read, write)]
+    fn buy_project_with_referrer(project_id: u64, referrer: Identity);
+
+    // buy several listed projects with a single payment
+    #[storage(read, write)]
+    fn buy_projects(project_ids: Vec<u64>);
+
+    // buy a listed project as a gift for the recipient
+    #[storage(read, write)]
+    fn buy_project_for(project_id: u64, recipient: Identity);
+
+    // sell 2-5 of your projects together for a single price
+    #[storage(read, write)]
+    fn create_bundle(project_ids: Vec<u64>, price: u64) -> Bundle;
+
+    // buy every project in a bundle you don't already own
+    #[storage(read, write)]
+    fn buy_bundle(bundle_id: u64);
+
+    // send a tip to the creator of a project
+    #[storage(read, write)]
+    fn tip_creator(project_id: u64);
+
+    // set the % of the creator's share paid to referrers for a project
+    #[storage(read, write)]
+    fn set_referral_rate(project_id: u64, rate: u64);
+
+    // set the % of each resale paid to the creator of a project
+    #[storage(read, write)]
+    fn set_royalty_rate(project_id: u64, rate: u64);
+
+    // list a bought project for resale at the given price
+    #[storage(read, write)]
+    fn list_license_for_resale(project_id: u64, price: u64);
+
+    // remove a resale listing
+    #[storage(read, write)]
+    fn cancel_resale(project_id: u64);
+
+    // buy a project listed for resale by the seller
+    #[storage(read, write)]
+    fn buy_resale(project_id: u64, seller: Identity);
+
+    // register the public key creators encrypt content keys to
+    #[storage(write)]
+    fn register_encryption_key(encryption_key: b256);
+
+    // deliver an encrypted content key to a buyer of your project
+    #[storage(read, write)]
+    fn deliver_key(project_id: u64, buyer: Identity, encrypted_key: [b256; 3]);
+
+    // set the profile shown for your projects
+    #[storage(write)]
+    fn set_creator_profile(display_name_hash: b256, profile: Metadata);
+
+    // price a project with a Dutch auction falling from start_price to floor_price
+    #[storage(read, write)]
+    fn start_dutch_auction(
+        project_id: u64,
+        start_price: u64,
+        floor_price: u64,
+        start_block: u64,
+        end_block: u64,
+    );
+
+    // go back to the listed price of a project
+    #[storage(read, write)]
+    fn end_dutch_auction(project_id: u64);
+
+    // sell a project with max_buyers = 1 by sealed bids, with its current price as the reserve
+    #[storage(read, write)]
+    fn start_sealed_auction(project_id: u64, commit_end_block: u64, reveal_end_block: u64);
+
+    // commit the hash of a bid, escrowing a deposit of at least the bid
+    #[storage(read, write)]
+    fn commit_bid(project_id: u64, commitment: b256);
+
+    // reveal a committed bid
+    #[storage(read, write)]
+    fn reveal_bid(project_id: u64, bid: u64, salt: b256);
+
+    // give the project to the highest bidder after the reveal phase
+    #[storage(read, write)]
+    fn settle_sealed_auction(project_id: u64);
+
+    // withdraw your deposit, minus your bid if you won
+    #[storage(read, write)]
+    fn withdraw_bid(project_id: u64);
+
+    // escrow payments for a project until a buyer or revenue goal is met by the deadline
+    #[storage(read, write)]
+    fn start_presale(project_id: u64, goal_buyers: u64, goal_revenue: u64, deadline_block: u64);
+
+    // send the escrowed payments of a pre-sale that met its goal to the creator
+    #[storage(read, write)]
+    fn release_presale(project_id: u64);
+
+    // refund your payment for a buyer's license from a pre-sale that missed its goal,
+    // which undoes their purchase
+    #[storage(read, write)]
+    fn claim_presale_refund(project_id: u64, buyer: Identity);
+
     // review a project you bought with a number 0-5
     #[storage(read, write)]
     fn review_project(project_id: u64, rating: u64) -> u64;
@@ -120,6 +1047,10 @@ abi WebGum {
     #[storage(read)]
     fn get_project(project_id: u64) -> Project;
 
+    // get a Project for a given project ID, or None if it doesn't exist
+    #[storage(read)]
+    fn try_get_project(project_id: u64) -> Option<Project>;
+
     // get the total number of projects listed
     #[storage(read)]
     fn get_projects_list_length() -> u64;
@@ -128,14 +1059,30 @@ abi WebGum {
     #[storage(read)]
     fn get_creator_list_length(creator: Identity) -> u64;
 
+    // get the profile, # of projects and # of sales of a given Identity
+    #[storage(read)]
+    fn get_creator(creator: Identity) -> Creator;
+
+    // get the total amount paid to a given creator
+    #[storage(read)]
+    fn get_creator_earnings(creator: Identity) -> u64;
+
     // get the nth Project created by a given Identity
     #[storage(read)]
     fn get_created_project(creator: Identity, index: u64) -> Project;
 
+    // get the nth Project created by a given Identity, or None if out of range
+    #[storage(read)]
+    fn try_get_created_project(creator: Identity, index: u64) -> Option<Project>;
+
     // get the nth project ID created by a given Identity
     #[storage(read)]
     fn get_created_project_id(creator: Identity, index: u64) -> u64;
 
+    // get the nth project ID created by a given Identity, or None if out of range
+    #[storage(read)]
+    fn try_get_created_project_id(creator: Identity, index: u64) -> Option<u64>;
+
     // get the number of projects bought by a given Identity
     #[storage(read)]
     fn get_buyer_list_length(buyer: Identity) -> u64;
@@ -144,17 +1091,41 @@ abi WebGum {
     #[storage(read)]
     fn get_bought_project(buyer: Identity, index: u64) -> Project;
 
+    // get the nth Project bought by a given Identity, or None if out of range
+    #[storage(read)]
+    fn try_get_bought_project(buyer: Identity, index: u64) -> Option<Project>;
+
     // check if the given Identity has bought the given project_id
     #[storage(read)]
     fn has_bought_project(project_id: u64, wallet: Identity) -> bool;
 
-    // get all of the project IDs created by a given Identity
+    // get the total number of purchases made
+    #[storage(read)]
+    fn get_purchase_count() -> u64;
+
+    // get the Purchase for a given purchase ID
     #[storage(read)]
-    fn get_creator_vector(id: Identity) -> Vector;
+    fn get_purchase(purchase_id: u64) -> Purchase;
 
-    // get all of the project IDs bought by a given Identity
+    // get a given Identity's Purchase of a project, or None if they don't hold it
     #[storage(read)]
-    fn get_buyer_vector(id: Identity) -> Vector;
+    fn get_purchase_by_buyer(project_id: u64, buyer: Identity) -> Option<Purchase>;
+
+    // get up to 5 Projects, starting at the given project ID
+    #[storage(read)]
+    fn get_projects(start: u64, count: u64) -> ProjectPage;
+
+    // get the Projects for up to 5 project IDs
+    #[storage(read)]
+    fn get_projects_by_ids(project_ids: Vec<u64>) -> ProjectPage;
+
+    // get up to 5 Projects created by a given Identity, starting at the given index
+    #[storage(read)]
+    fn get_created_projects(creator: Identity, start: u64, count: u64) -> ProjectPage;
+
+    // get up to 5 Projects bought by a given Identity, starting at the given index
+    #[storage(read)]
+    fn get_bought_projects(buyer: Identity, start: u64, count: u64) -> ProjectPage;
 
     // get the index locations of the reviews for a given project
     #[storage(read)]
@@ -164,20 +1135,170 @@ abi WebGum {
     #[storage(read)]
     fn get_project_rating(index: u64) -> (Identity, u64);
 
-    // a function to set the contract owner
-    #[storage(read, write)]
-    fn initialize_owner() -> Identity;
+    // get the referral % for a given project
+    #[storage(read)]
+    fn get_referral_rate(project_id: u64) -> u64;
 
-    // a function to withdraw contract funds
+    // get the number of sales referred by a given Identity
     #[storage(read)]
-    fn withdraw_funds();
-}
+    fn get_referral_sales(referrer: Identity) -> u64;
 
-impl WebGum for Contract {
+    // get the total referral earnings of a given Identity
+    #[storage(read)]
+    fn get_referral_earnings(referrer: Identity) -> u64;
+
+    // get the royalty % for a given project
+    #[storage(read)]
+    fn get_royalty_rate(project_id: u64) -> u64;
+
+    // get the resale price of a project listed by the seller
+    #[storage(read)]
+    fn get_resale_price(project_id: u64, seller: Identity) -> Option<u64>;
+
+    // get the number of metadata versions of a given project
+    #[storage(read)]
+    fn get_metadata_version_count(project_id: u64) -> u64;
+
+    // get the nth metadata version of a given project
+    #[storage(read)]
+    fn get_metadata_version(project_id: u64, version: u64) -> MetadataVersion;
+
+    // get the metadata version # a given Identity bought
+    #[storage(read)]
+    fn get_purchased_version(project_id: u64, buyer: Identity) -> Option<u64>;
+
+    // get the registered public key of a given Identity
+    #[storage(read)]
+    fn get_encryption_key(wallet: Identity) -> Option<b256>;
+
+    // get the content key delivered to a buyer, only callable by the buyer or creator
+    #[storage(read)]
+    fn get_delivered_key(project_id: u64, buyer: Identity) -> Option<KeyDelivery>;
+
+    // get the nth buyer of a given project
+    #[storage(read)]
+    fn get_project_buyer(project_id: u64, index: u64) -> Identity;
+
+    // get up to 5 buyers of a given project, starting at the given index
+    #[storage(read)]
+    fn get_project_buyers(project_id: u64, start: u64) -> BuyerPage;
+
+    // add a category, only callable by the contract owner
+    #[storage(read, write)]
+    fn add_category(name_hash: b256) -> u64;
+
+    // get the total number of categories
+    #[storage(read)]
+    fn get_category_count() -> u64;
+
+    // get the name hash of a given category
+    #[storage(read)]
+    fn get_category(category: u64) -> b256;
+
+    // get the number of projects in a given category
+    #[storage(read)]
+    fn get_category_project_count(category: u64) -> u64;
+
+    // get the nth Project in a given category
+    #[storage(read)]
+    fn get_category_project(category: u64, index: u64) -> Project;
+
+    // get up to 5 project IDs in a given category, starting at the given index
+    #[storage(read)]
+    fn get_category_project_ids(category: u64, start: u64) -> Vector;
+
+    // get the Dutch auction of a given project, if it has one
+    #[storage(read)]
+    fn get_dutch_auction(project_id: u64) -> Option<DutchAuction>;
+
+    // get the current price of a given project
+    #[storage(read)]
+    fn get_current_price(project_id: u64) -> u64;
+
+    // get the sealed-bid auction of a given project, if it has one
+    #[storage(read)]
+    fn get_sealed_auction(project_id: u64) -> Option<SealedAuction>;
+
+    // get the sealed bid of a given Identity
+    #[storage(read)]
+    fn get_sealed_bid(project_id: u64, bidder: Identity) -> Option<SealedBid>;
+
+    // get a bundle by its id
+    #[storage(read)]
+    fn get_bundle(bundle_id: u64) -> Option<Bundle>;
+
+    // get the total amount tipped to the creator of a given project
+    #[storage(read)]
+    fn get_project_tips(project_id: u64) -> u64;
+
+    // check if the contract owner blocked a given Identity
+    #[storage(read)]
+    fn is_blocked(wallet: Identity) -> bool;
+
+    // get the reason code a given project was hidden for, if it's hidden
+    #[storage(read)]
+    fn get_hidden_reason(project_id: u64) -> Option<u64>;
+
+    // get the pre-sale of a given project, if it has one
+    #[storage(read)]
+    fn get_presale(project_id: u64) -> Option<Presale>;
+
+    // get marketplace-wide totals
+    #[storage(read)]
+    fn get_marketplace_stats() -> MarketplaceStats;
+
+    // get the total amount of a given asset paid for projects
+    #[storage(read)]
+    fn get_asset_volume(asset_id: ContractId) -> u64;
+
+    // a function to set the contract owner
+    #[storage(read, write)]
+    fn initialize_owner() -> Identity;
+
+    // a function to withdraw contract funds
+    #[storage(read)]
+    fn withdraw_funds();
+
+    // a function to set if the contract keeps commission from tips
+    #[storage(read, write)]
+    fn set_tip_commission(enabled: bool);
+
+    // a function to stop a project from being bought
+    #[storage(read, write)]
+    fn hide_project(project_id: u64, reason_code: u64);
+
+    // a function to let a hidden project be bought again
+    #[storage(read, write)]
+    fn unhide_project(project_id: u64);
+
+    // a function to remove a review, by the id review_project returned, from its project's ratings
+    #[storage(read, write)]
+    fn remove_review(review_id: u64);
+
+    // a function to stop an Identity from listing, buying, reselling, bidding on or reviewing projects,
+    // and from earning referrals. projects it already bought stay bought and can still be accessed.
+    // projects it created can't be updated, sold, resold or tipped, and escrowed pre-sale payments
+    // aren't released to it until it's unblocked
+    #[storage(read, write)]
+    fn block_identity(wallet: Identity);
+
+    // a function to lift a block on an Identity
+    #[storage(read, write)]
+    fn unblock_identity(wallet: Identity);
+}
+
+impl WebGum for Contract {
     #[storage(read, write)]
-    fn list_project(price: u64, max_buyers: u64, metadata: str[59]) -> Project {
+    fn list_project(
+        price: u64,
+        max_buyers: u64,
+        metadata: Metadata,
+        category: u64,
+        tags: [u64; 3],
+    ) -> Project {
         let index = storage.project_count;
         let sender: Result<Identity, AuthError> = msg_sender();
+        require_not_blocked(sender.unwrap());
 
         let newProject = Project {
             project_id: index,
@@ -187,17 +1308,28 @@ impl WebGum for Contract {
             buyer_count: 0,
             owner_address: sender.unwrap(),
             metadata: metadata,
+            category: category,
+            tags: tags,
+            total_revenue: 0,
+            total_commission: 0,
         };
 
         let mut existing: Vector = storage.creators.get(sender.unwrap());
+        if existing.current_ix == 0 {
+            storage.unique_creator_count = storage.unique_creator_count + 1;
+        }
 
-        // add msg sender to buyer list
+        // add msg sender to creator list
         existing.push(index);
         storage.creators.insert(sender.unwrap(), existing);
 
         storage.project_listings.insert(index, newProject);
         storage.project_count = storage.project_count + 1;
 
+        // the listed metadata is version 0
+        add_metadata_version(index, metadata, Option::None);
+        add_to_category(index, category);
+
         return newProject
     }
     
@@ -206,22 +1338,42 @@ impl WebGum for Contract {
         project_id: u64,
         price: u64,
         max_buyers: u64,
-        metadata: str[59],
+        metadata: Metadata,
+        changelog: Option<Metadata>,
+        category: u64,
+        tags: [u64; 3],
     ) -> Project {
-        let mut project: Project = storage.project_listings.get(project_id);
+        let mut project: Project = get_existing_project(project_id);
 
         // only allow the owner to update
         let sender: Result<Identity, AuthError> = msg_sender();
         require(sender.unwrap() == project.owner_address, InvalidError::NotProjectOwner);
-        if max_buyers > 0 {
+        require_not_blocked(sender.unwrap());
+        if max_buyers > 0 && max_buyers != project.max_buyers {
             // make sure new max_buyers isn't less than buyer_count
             require(max_buyers > project.buyer_count, InvalidError::MaxBuyers);
         }
+        // a project sold by sealed bids has to stay one-of-one
+        if storage.sealed_auctions.get(project_id).is_some() {
+            require(max_buyers == project.max_buyers, InvalidError::NotOneOfOne);
+        }
+
+        // add changed metadata as the next version
+        if metadata != project.metadata {
+            add_metadata_version(project_id, metadata, changelog);
+        }
+
+        if category != project.category {
+            remove_from_category(project_id, project.category);
+            add_to_category(project_id, category);
+        }
 
         // update project
         project.price = price;
         project.metadata = metadata;
         project.max_buyers = max_buyers;
+        project.category = category;
+        project.tags = tags;
         storage.project_listings.insert(project_id, project);
 
         return project;
@@ -232,58 +1384,626 @@ impl WebGum for Contract {
         let asset_id = msg_asset_id();
         let amount = msg_amount();
 
-        let mut project: Project = storage.project_listings.get(project_id);
+        let sender: Result<Identity, AuthError> = msg_sender();
+        let charge = get_charge(project_id, amount);
+        let commission = get_commission(charge);
+        let project = add_buyer(project_id, sender.unwrap(), asset_id, charge, commission);
 
-        if (project.max_buyers > 0) {
-            // require buyer_count to be less than the max_buyers limit
-            require(project.max_buyers > project.buyer_count, InvalidError::MaxBuyers);
+        // require payment
+        require(asset_id == BASE_ASSET_ID, InvalidError::IncorrectAssetId);
+
+        pay_seller(project, asset_id, charge, commission);
+
+        // refund the rest of an auction payment
+        if amount > charge {
+            transfer(amount - charge, asset_id, sender.unwrap());
         }
+    }
 
-        // add 1 to the buyer count
-        project.update_buyer_count();
-        // update project_listings
-        storage.project_listings.insert(project_id, project);
+    #[storage(read, write)]
+    fn buy_project_with_referrer(project_id: u64, referrer: Identity) {
+        let asset_id = msg_asset_id();
+        let amount = msg_amount();
+
+        let sender: Result<Identity, AuthError> = msg_sender();
+        // no self-referrals
+        require(referrer != sender.unwrap(), InvalidError::InvalidReferrer);
+
+        let charge = get_charge(project_id, amount);
+        let commission = get_commission(charge);
+        let project = add_buyer(project_id, sender.unwrap(), asset_id, charge, commission);
+        // the creator can't refer their own project
+        require(referrer != project.owner_address, InvalidError::InvalidReferrer);
+        require_not_blocked(referrer);
+        // referrals are paid right away, so they can't be refunded with a pre-sale
+        require(!presale_active(project_id), InvalidError::PresaleActive);
 
         // require payment
         require(asset_id == BASE_ASSET_ID, InvalidError::IncorrectAssetId);
-        require(amount >= project.price, InvalidError::NotEnoughTokens);
+
+        // the referral is paid out of the creator's share
+        let payout = charge - commission;
+        let referral = payout * storage.referral_rates.get(project_id) / 100;
+
+        storage.referral_sales.insert(referrer, storage.referral_sales.get(referrer) + 1);
+        storage.referral_earnings.insert(referrer, storage.referral_earnings.get(referrer) + referral);
+        storage.creator_earnings.insert(project.owner_address, storage.creator_earnings.get(project.owner_address) - referral);
+
+        if referral > 0 {
+            transfer(referral, asset_id, referrer);
+        }
+        if payout > referral {
+            transfer(payout - referral, asset_id, project.owner_address);
+        }
+
+        // refund the rest of an auction payment
+        if amount > charge {
+            transfer(amount - charge, asset_id, sender.unwrap());
+        }
+    }
+
+    #[storage(read, write)]
+    fn buy_projects(project_ids: Vec<u64>) {
+        let asset_id = msg_asset_id();
+        let amount = msg_amount();
 
         let sender: Result<Identity, AuthError> = msg_sender();
 
-        let mut existing: Vector = storage.buyers.get(sender.unwrap());
+        // add the sender to every project in the cart and add up the prices
+        let mut total = 0;
+        let mut i = 0;
+        while i < project_ids.len() {
+            let project_id = project_ids.get(i).unwrap();
+            let price = current_price(project_id);
+            let _project = add_buyer(project_id, sender.unwrap(), asset_id, price, get_commission(price));
+            total = total + price;
+            i += 1;
+        }
 
-        // add msg sender to buyer list
-        existing.push(project_id);
-        storage.buyers.insert(sender.unwrap(), existing);
+        // require payment for the whole cart
+        require(asset_id == BASE_ASSET_ID, InvalidError::IncorrectAssetId);
+        require(amount >= total, InvalidError::NotEnoughTokens);
 
-        // only charge commission if price is more than 1_000
-        if amount > 1_000 {
-            // for every 100 coins, the contract keeps 5
-            let commission = amount / 20;
-            let new_amount = amount - commission;
-            // send the payout minus commission to the seller
-            transfer(new_amount, asset_id, project.owner_address);
-        } else {
-        // send the full payout to the seller
-            transfer(amount, asset_id, project.owner_address);
+        // pay each seller their price minus commission
+        let mut i = 0;
+        while i < project_ids.len() {
+            let project_id = project_ids.get(i).unwrap();
+            let project: Project = get_existing_project(project_id);
+            let price = current_price(project_id);
+            pay_seller(project, asset_id, price, get_commission(price));
+            i += 1;
+        }
+
+        // send any change back to the sender
+        if amount > total {
+            transfer(amount - total, asset_id, sender.unwrap());
         }
     }
 
     #[storage(read, write)]
-    fn review_project(project_id: u64, rating: u64) -> u64 {
-        require(rating < 6, InvalidError::InvalidRating);
+    fn buy_project_for(project_id: u64, recipient: Identity) {
+        let asset_id = msg_asset_id();
+        let amount = msg_amount();
+
+        // the recipient gets the buyer entry, the sender pays
+        let sender: Result<Identity, AuthError> = msg_sender();
+        require_not_blocked(sender.unwrap());
+        let charge = get_charge(project_id, amount);
+        let commission = get_commission(charge);
+        let project = add_buyer(project_id, recipient, asset_id, charge, commission);
+
+        // the gifter gets any pre-sale refund
+        if presale_active(project_id) {
+            storage.presale_payers.insert((project_id, recipient), Option::Some(sender.unwrap()));
+        }
+
+        // require payment
+        require(asset_id == BASE_ASSET_ID, InvalidError::IncorrectAssetId);
+
+        log(GiftEvent {
+            project_id: project_id,
+            gifter: sender.unwrap(),
+            recipient: recipient,
+        });
+
+        pay_seller(project, asset_id, charge, commission);
+
+        // refund the rest of an auction payment
+        if amount > charge {
+            transfer(amount - charge, asset_id, sender.unwrap());
+        }
+    }
+
+    #[storage(read, write)]
+    fn create_bundle(project_ids: Vec<u64>, price: u64) -> Bundle {
+        require(project_ids.len() > 1 && project_ids.len() <= PAGE_SIZE, InvalidError::InvalidBundle);
+
         let sender: Result<Identity, AuthError> = msg_sender();
-        let mut existing: Vector = storage.buyers.get(sender.unwrap());
+        require_not_blocked(sender.unwrap());
+        let mut projects = Vector::new();
         let mut i = 0;
-        let mut can_review = false;
-        while i < existing.current_ix {
-            let project = existing.get(i);
-            if project == project_id {
-                can_review = true;
+        while i < project_ids.len() {
+            let project_id = project_ids.get(i).unwrap();
+            let project: Project = get_existing_project(project_id);
+
+            // creators can only bundle their own projects, each one once
+            require(sender.unwrap() == project.owner_address, InvalidError::NotProjectOwner);
+            require(!projects.contains(project_id), InvalidError::InvalidBundle);
+
+            projects.push(project_id);
+            i += 1;
+        }
+
+        let bundle = Bundle {
+            bundle_id: storage.bundle_count,
+            owner_address: sender.unwrap(),
+            projects: projects,
+            price: price,
+        };
+        storage.bundles.insert(bundle.bundle_id, Option::Some(bundle));
+        storage.bundle_count = storage.bundle_count + 1;
+
+        bundle
+    }
+
+    #[storage(read, write)]
+    fn buy_bundle(bundle_id: u64) {
+        let asset_id = msg_asset_id();
+        let amount = msg_amount();
+
+        let bundle = storage.bundles.get(bundle_id);
+        require(bundle.is_some(), InvalidError::BundleNotFound);
+        let bundle = bundle.unwrap();
+
+        // require payment
+        require(asset_id == BASE_ASSET_ID, InvalidError::IncorrectAssetId);
+
+        // projects the sender already owns are skipped and their share isn't charged
+        let sender: Result<Identity, AuthError> = msg_sender();
+        let mut total = 0;
+        let mut bought = 0;
+        let mut i = 0;
+        while i < bundle.projects.current_ix {
+            if !has_bought(bundle.projects.inner[i], sender.unwrap()) {
+                total = total + get_bundle_share(bundle, i);
+                bought += 1;
             }
             i += 1;
         }
 
+        require(bought > 0, InvalidError::AlreadyBought);
+        require(amount >= total, InvalidError::NotEnoughTokens);
+
+        // commission is taken once on the whole charge and split evenly between the projects bought,
+        // with any remainder of the split taken from the last one
+        let commission = get_commission(total);
+        let mut commission_left = commission;
+        let mut left = bought;
+        let mut i = 0;
+        while i < bundle.projects.current_ix {
+            let project_id = bundle.projects.inner[i];
+            if !has_bought(project_id, sender.unwrap()) {
+                // projects in a sealed-bid auction can only be won
+                let sealed_auction = storage.sealed_auctions.get(project_id);
+                require(sealed_auction.is_none() || sealed_auction.unwrap().settled, InvalidError::AuctionActive);
+
+                left -= 1;
+                let mut project_commission = commission / bought;
+                if left == 0 {
+                    project_commission = commission_left;
+                }
+                commission_left = commission_left - project_commission;
+
+                let share = get_bundle_share(bundle, i);
+                let project = add_buyer(project_id, sender.unwrap(), asset_id, share, project_commission);
+                pay_seller(project, asset_id, share, project_commission);
+            }
+            i += 1;
+        }
+
+        // send any change back to the sender
+        if amount > total {
+            transfer(amount - total, asset_id, sender.unwrap());
+        }
+    }
+
+    #[storage(read, write)]
+    fn tip_creator(project_id: u64) {
+        let asset_id = msg_asset_id();
+        let amount = msg_amount();
+
+        let project: Project = get_existing_project(project_id);
+        require(!is_hidden(project_id), InvalidError::ProjectHidden);
+        require_not_blocked(project.owner_address);
+
+        // require payment
+        require(asset_id == BASE_ASSET_ID, InvalidError::IncorrectAssetId);
+        require(amount > 0, InvalidError::InvalidTip);
+
+        let commission = if storage.tip_commission {
+            get_commission(amount)
+        } else {
+            0
+        };
+
+        storage.project_tips.insert(project_id, storage.project_tips.get(project_id) + amount);
+        add_creator_earnings(project.owner_address, amount - commission);
+
+        let sender: Result<Identity, AuthError> = msg_sender();
+        log(TipEvent {
+            project_id: project_id,
+            tipper: sender.unwrap(),
+            creator: project.owner_address,
+            amount: amount,
+            commission: commission,
+        });
+
+        // send the tip minus commission to the creator
+        transfer(amount - commission, asset_id, project.owner_address);
+    }
+
+    #[storage(read, write)]
+    fn set_referral_rate(project_id: u64, rate: u64) {
+        let project: Project = get_existing_project(project_id);
+
+        // only the creator can set the referral rate
+        let sender: Result<Identity, AuthError> = msg_sender();
+        require(sender.unwrap() == project.owner_address, InvalidError::NotProjectOwner);
+        require(rate <= 100, InvalidError::InvalidReferralRate);
+
+        storage.referral_rates.insert(project_id, rate);
+    }
+
+    #[storage(read, write)]
+    fn set_royalty_rate(project_id: u64, rate: u64) {
+        let project: Project = get_existing_project(project_id);
+
+        // only the creator can set the royalty rate
+        let sender: Result<Identity, AuthError> = msg_sender();
+        require(sender.unwrap() == project.owner_address, InvalidError::NotProjectOwner);
+        require(rate <= 100, InvalidError::InvalidRoyaltyRate);
+
+        storage.royalty_rates.insert(project_id, rate);
+    }
+
+    #[storage(read, write)]
+    fn list_license_for_resale(project_id: u64, price: u64) {
+        let sender: Result<Identity, AuthError> = msg_sender();
+        // require sender has bought the project
+        require(has_access(project_id, sender.unwrap()), InvalidError::NotLicenseHolder);
+        require_not_blocked(sender.unwrap());
+
+        storage.resale_listings.insert((project_id, sender.unwrap()), Option::Some(price));
+    }
+
+    #[storage(read, write)]
+    fn cancel_resale(project_id: u64) {
+        let sender: Result<Identity, AuthError> = msg_sender();
+        storage.resale_listings.insert((project_id, sender.unwrap()), Option::None);
+    }
+
+    #[storage(read, write)]
+    fn buy_resale(project_id: u64, seller: Identity) {
+        let asset_id = msg_asset_id();
+        let amount = msg_amount();
+
+        let listing = storage.resale_listings.get((project_id, seller));
+        // the seller must have listed the project and still hold the license
+        require(listing.is_some(), InvalidError::NotListedForResale);
+        require(has_access(project_id, seller), InvalidError::NotListedForResale);
+        require(!is_hidden(project_id), InvalidError::ProjectHidden);
+        let price = listing.unwrap();
+
+        // require payment
+        require(asset_id == BASE_ASSET_ID, InvalidError::IncorrectAssetId);
+        require(amount >= price, InvalidError::NotEnoughTokens);
+
+        // move the license, buyer_count stays the same
+        let sender: Result<Identity, AuthError> = msg_sender();
+        require_not_blocked(sender.unwrap());
+        require_not_blocked(seller);
+        require_not_blocked(storage.project_listings.get(project_id).owner_address);
+        move_license(project_id, seller, sender.unwrap(), asset_id, amount);
+        storage.resale_listings.insert((project_id, seller), Option::None);
+
+        // the royalty is paid out of the seller's share
+        let project: Project = get_existing_project(project_id);
+        let payout = amount - get_commission(amount);
+        let royalty = payout * storage.royalty_rates.get(project_id) / 100;
+        add_creator_earnings(project.owner_address, royalty);
+
+        if royalty > 0 {
+            transfer(royalty, asset_id, project.owner_address);
+        }
+        if payout > royalty {
+            transfer(payout - royalty, asset_id, seller);
+        }
+    }
+
+    #[storage(write)]
+    fn register_encryption_key(encryption_key: b256) {
+        let sender: Result<Identity, AuthError> = msg_sender();
+        storage.encryption_keys.insert(sender.unwrap(), Option::Some(encryption_key));
+    }
+
+    #[storage(read, write)]
+    fn deliver_key(project_id: u64, buyer: Identity, encrypted_key: [b256; 3]) {
+        let project: Project = get_existing_project(project_id);
+
+        // only allow the owner to deliver keys
+        let sender: Result<Identity, AuthError> = msg_sender();
+        require(sender.unwrap() == project.owner_address, InvalidError::NotProjectOwner);
+        require(has_access(project_id, buyer), InvalidError::NotLicenseHolder);
+
+        let encryption_key = storage.encryption_keys.get(buyer);
+        require(encryption_key.is_some(), InvalidError::KeyNotRegistered);
+
+        storage.delivered_keys.insert((project_id, buyer), Option::Some(KeyDelivery {
+            encryption_key: encryption_key.unwrap(),
+            encrypted_key: encrypted_key,
+            block_height: height(),
+        }));
+    }
+
+    #[storage(write)]
+    fn set_creator_profile(display_name_hash: b256, profile: Metadata) {
+        let sender: Result<Identity, AuthError> = msg_sender();
+        storage.creator_profiles.insert(sender.unwrap(), Option::Some(CreatorProfile {
+            display_name_hash: display_name_hash,
+            profile: profile,
+        }));
+    }
+
+    #[storage(read, write)]
+    fn start_dutch_auction(
+        project_id: u64,
+        start_price: u64,
+        floor_price: u64,
+        start_block: u64,
+        end_block: u64,
+    ) {
+        let project: Project = get_existing_project(project_id);
+
+        // only the creator can start an auction
+        let sender: Result<Identity, AuthError> = msg_sender();
+        require(sender.unwrap() == project.owner_address, InvalidError::NotProjectOwner);
+        require_not_blocked(sender.unwrap());
+        require(start_price >= floor_price, InvalidError::InvalidAuction);
+        require(end_block > start_block, InvalidError::InvalidAuction);
+
+        storage.dutch_auctions.insert(project_id, Option::Some(DutchAuction {
+            start_price: start_price,
+            floor_price: floor_price,
+            start_block: start_block,
+            end_block: end_block,
+        }));
+    }
+
+    #[storage(read, write)]
+    fn end_dutch_auction(project_id: u64) {
+        let project: Project = get_existing_project(project_id);
+
+        // only the creator can end an auction
+        let sender: Result<Identity, AuthError> = msg_sender();
+        require(sender.unwrap() == project.owner_address, InvalidError::NotProjectOwner);
+
+        storage.dutch_auctions.insert(project_id, Option::None);
+    }
+
+    #[storage(read, write)]
+    fn start_sealed_auction(project_id: u64, commit_end_block: u64, reveal_end_block: u64) {
+        let project: Project = get_existing_project(project_id);
+
+        // only the creator can start an auction
+        let sender: Result<Identity, AuthError> = msg_sender();
+        require(sender.unwrap() == project.owner_address, InvalidError::NotProjectOwner);
+        require_not_blocked(sender.unwrap());
+        require(project.max_buyers == 1 && project.buyer_count == 0, InvalidError::NotOneOfOne);
+        require(storage.sealed_auctions.get(project_id).is_none(), InvalidError::AuctionActive);
+        require(!has_open_presale(project_id), InvalidError::InvalidAuction);
+        require(commit_end_block > height(), InvalidError::InvalidAuction);
+        require(reveal_end_block > commit_end_block, InvalidError::InvalidAuction);
+
+        storage.sealed_auctions.insert(project_id, Option::Some(SealedAuction {
+            commit_end_block: commit_end_block,
+            reveal_end_block: reveal_end_block,
+            reserve_price: project.price,
+            highest_bid: 0,
+            highest_bidder: Option::None,
+            settled: false,
+        }));
+    }
+
+    #[storage(read, write)]
+    fn commit_bid(project_id: u64, commitment: b256) {
+        let auction = storage.sealed_auctions.get(project_id);
+        require(auction.is_some() && height() < auction.unwrap().commit_end_block, InvalidError::NotCommitPhase);
+        require(!is_hidden(project_id), InvalidError::ProjectHidden);
+
+        // require deposit
+        require(msg_asset_id() == BASE_ASSET_ID, InvalidError::IncorrectAssetId);
+        require(msg_amount() > 0, InvalidError::NotEnoughTokens);
+
+        let sender: Result<Identity, AuthError> = msg_sender();
+        require_not_blocked(sender.unwrap());
+        require(storage.sealed_bids.get((project_id, sender.unwrap())).is_none(), InvalidError::AlreadyCommitted);
+
+        storage.sealed_bids.insert((project_id, sender.unwrap()), Option::Some(SealedBid {
+            commitment: commitment,
+            deposit: msg_amount(),
+            revealed_bid: Option::None,
+            withdrawn: false,
+        }));
+        storage.escrow_balance = storage.escrow_balance + msg_amount();
+    }
+
+    #[storage(read, write)]
+    fn reveal_bid(project_id: u64, bid: u64, salt: b256) {
+        let auction = storage.sealed_auctions.get(project_id);
+        require(auction.is_some(), InvalidError::NotRevealPhase);
+        let mut auction = auction.unwrap();
+        let block = height();
+        require(block >= auction.commit_end_block && block < auction.reveal_end_block, InvalidError::NotRevealPhase);
+
+        let sender: Result<Identity, AuthError> = msg_sender();
+        let sealed_bid = storage.sealed_bids.get((project_id, sender.unwrap()));
+        require(sealed_bid.is_some(), InvalidError::BidNotFound);
+        let mut sealed_bid = sealed_bid.unwrap();
+
+        // the bid must match the commitment and be covered by the deposit
+        require(sealed_bid.revealed_bid.is_none(), InvalidError::InvalidReveal);
+        require(sha256((bid, salt)) == sealed_bid.commitment, InvalidError::InvalidReveal);
+        require(bid <= sealed_bid.deposit, InvalidError::InvalidReveal);
+
+        sealed_bid.revealed_bid = Option::Some(bid);
+        storage.sealed_bids.insert((project_id, sender.unwrap()), Option::Some(sealed_bid));
+
+        // ties go to the first bid revealed
+        if auction.highest_bidder.is_none() || bid > auction.highest_bid {
+            auction.highest_bid = bid;
+            auction.highest_bidder = Option::Some(sender.unwrap());
+            storage.sealed_auctions.insert(project_id, Option::Some(auction));
+        }
+    }
+
+    #[storage(read, write)]
+    fn settle_sealed_auction(project_id: u64) {
+        let auction = storage.sealed_auctions.get(project_id);
+        require(auction.is_some(), InvalidError::AuctionNotEnded);
+        let mut auction = auction.unwrap();
+        require(height() >= auction.reveal_end_block, InvalidError::AuctionNotEnded);
+        require(!auction.settled, InvalidError::AuctionNotEnded);
+
+        // a hidden project, a blocked winner or creator, or a highest bid under the reserve means no sale,
+        // so every bidder can withdraw their deposit
+        let winner_blocked = auction.highest_bidder.is_some() && storage.blocked.get(auction.highest_bidder.unwrap());
+        let creator_blocked = storage.blocked.get(storage.project_listings.get(project_id).owner_address);
+        let below_reserve = auction.highest_bid < auction.reserve_price;
+        if is_hidden(project_id) || winner_blocked || creator_blocked || below_reserve {
+            auction.highest_bidder = Option::None;
+        }
+        auction.settled = true;
+        storage.sealed_auctions.insert(project_id, Option::Some(auction));
+
+        // with no revealed bids the project stays unsold
+        if auction.highest_bidder.is_some() {
+            let winner = auction.highest_bidder.unwrap();
+            let commission = get_commission(auction.highest_bid);
+            let project = add_buyer(project_id, winner, BASE_ASSET_ID, auction.highest_bid, commission);
+            storage.escrow_balance = storage.escrow_balance - auction.highest_bid;
+
+            // send the winning bid minus commission to the seller
+            if auction.highest_bid > 0 {
+                transfer(auction.highest_bid - commission, BASE_ASSET_ID, project.owner_address);
+            }
+        }
+    }
+
+    #[storage(read, write)]
+    fn withdraw_bid(project_id: u64) {
+        let auction = storage.sealed_auctions.get(project_id);
+        require(auction.is_some() && auction.unwrap().settled, InvalidError::AuctionNotSettled);
+        let auction = auction.unwrap();
+
+        let sender: Result<Identity, AuthError> = msg_sender();
+        let sealed_bid = storage.sealed_bids.get((project_id, sender.unwrap()));
+        require(sealed_bid.is_some(), InvalidError::BidNotFound);
+        let mut sealed_bid = sealed_bid.unwrap();
+        require(!sealed_bid.withdrawn, InvalidError::AlreadyWithdrawn);
+
+        sealed_bid.withdrawn = true;
+        storage.sealed_bids.insert((project_id, sender.unwrap()), Option::Some(sealed_bid));
+
+        // the winner's bid was paid to the seller
+        let mut refund = sealed_bid.deposit;
+        if auction.highest_bidder.is_some() && auction.highest_bidder.unwrap() == sender.unwrap() {
+            refund = refund - auction.highest_bid;
+        }
+        if refund > 0 {
+            storage.escrow_balance = storage.escrow_balance - refund;
+            transfer(refund, BASE_ASSET_ID, sender.unwrap());
+        }
+    }
+
+    #[storage(read, write)]
+    fn start_presale(project_id: u64, goal_buyers: u64, goal_revenue: u64, deadline_block: u64) {
+        let project: Project = get_existing_project(project_id);
+
+        // only the creator can start a pre-sale
+        let sender: Result<Identity, AuthError> = msg_sender();
+        require(sender.unwrap() == project.owner_address, InvalidError::NotProjectOwner);
+        require_not_blocked(sender.unwrap());
+        require(project.buyer_count == 0, InvalidError::InvalidPresale);
+        require(!has_open_presale(project_id), InvalidError::InvalidPresale);
+        require(storage.sealed_auctions.get(project_id).is_none(), InvalidError::InvalidPresale);
+        require(goal_buyers > 0 || goal_revenue > 0, InvalidError::InvalidPresale);
+        require(deadline_block > height(), InvalidError::InvalidPresale);
+
+        storage.presales.insert(project_id, Option::Some(Presale {
+            goal_buyers: goal_buyers,
+            goal_revenue: goal_revenue,
+            deadline_block: deadline_block,
+            raised: 0,
+            commission: 0,
+            goal_met: false,
+            released: false,
+        }));
+    }
+
+    #[storage(read, write)]
+    fn release_presale(project_id: u64) {
+        let presale = storage.presales.get(project_id);
+        require(presale.is_some() && presale.unwrap().goal_met, InvalidError::PresaleActive);
+        let mut presale = presale.unwrap();
+        require(!presale.released, InvalidError::PresaleActive);
+        // a blocked creator's escrow stays held until they're unblocked
+        let project: Project = get_existing_project(project_id);
+        require_not_blocked(project.owner_address);
+
+        presale.released = true;
+        storage.presales.insert(project_id, Option::Some(presale));
+        storage.escrow_balance = storage.escrow_balance - presale.raised;
+
+        // send the payout minus commission to the seller
+        let payout = presale.raised - presale.commission;
+        add_creator_earnings(project.owner_address, payout);
+        if payout > 0 {
+            transfer(payout, BASE_ASSET_ID, project.owner_address);
+        }
+    }
+
+    #[storage(read, write)]
+    fn claim_presale_refund(project_id: u64, buyer: Identity) {
+        let presale = storage.presales.get(project_id);
+        require(presale.is_some() && presale_failed(presale.unwrap()), InvalidError::PresaleNotFailed);
+        let mut presale = presale.unwrap();
+
+        // only the Identity that paid gets the refund
+        let payer = storage.presale_payers.get((project_id, buyer));
+        require(payer.is_some(), InvalidError::PurchaseNotFound);
+        let sender: Result<Identity, AuthError> = msg_sender();
+        require(sender.unwrap() == payer.unwrap(), InvalidError::NotPresalePayer);
+
+        storage.presale_payers.insert((project_id, buyer), Option::None);
+        let purchase = remove_buyer(project_id, buyer);
+
+        presale.raised = presale.raised - purchase.amount;
+        presale.commission = presale.commission - purchase.commission;
+        storage.presales.insert(project_id, Option::Some(presale));
+
+        if purchase.amount > 0 {
+            storage.escrow_balance = storage.escrow_balance - purchase.amount;
+            transfer(purchase.amount, purchase.asset_id, sender.unwrap());
+        }
+    }
+
+    #[storage(read, write)]
+    fn review_project(project_id: u64, rating: u64) -> u64 {
+        require(rating < 6, InvalidError::InvalidRating);
+        let sender: Result<Identity, AuthError> = msg_sender();
+        require_not_blocked(sender.unwrap());
+        let can_review = has_access(project_id, sender.unwrap());
+
         // require sender has bought the project
         require(can_review, InvalidError::CantReview);
 
@@ -300,7 +2020,16 @@ impl WebGum for Contract {
 
     #[storage(read)]
     fn get_project(project_id: u64) -> Project {
-        storage.project_listings.get(project_id)
+        get_existing_project(project_id)
+    }
+
+    #[storage(read)]
+    fn try_get_project(project_id: u64) -> Option<Project> {
+        if project_id < storage.project_count {
+            Option::Some(storage.project_listings.get(project_id))
+        } else {
+            Option::None
+        }
     }
 
     #[storage(read)]
@@ -313,10 +2042,32 @@ impl WebGum for Contract {
         storage.creators.get(creator).current_ix
     }
 
+    #[storage(read)]
+    fn get_creator(creator: Identity) -> Creator {
+        Creator {
+            profile: storage.creator_profiles.get(creator),
+            project_count: storage.creators.get(creator).current_ix,
+            sales: storage.creator_sales.get(creator),
+            earnings: storage.creator_earnings.get(creator),
+        }
+    }
+
+    #[storage(read)]
+    fn get_creator_earnings(creator: Identity) -> u64 {
+        storage.creator_earnings.get(creator)
+    }
+
     #[storage(read)]
     fn get_created_project(creator: Identity, index: u64) -> Project {
-        let project_id = storage.creators.get(creator).get(index);
-        storage.project_listings.get(project_id)
+        get_existing_project(storage.creators.get(creator).get(index))
+    }
+
+    #[storage(read)]
+    fn try_get_created_project(creator: Identity, index: u64) -> Option<Project> {
+        match storage.creators.get(creator).try_get(index) {
+            Option::Some(project_id) => Option::Some(get_existing_project(project_id)),
+            Option::None => Option::None,
+        }
     }
 
     #[storage(read)]
@@ -324,6 +2075,11 @@ impl WebGum for Contract {
         storage.creators.get(creator).get(index)
     }
 
+    #[storage(read)]
+    fn try_get_created_project_id(creator: Identity, index: u64) -> Option<u64> {
+        storage.creators.get(creator).try_get(index)
+    }
+
     #[storage(read)]
     fn get_buyer_list_length(buyer: Identity) -> u64 {
         storage.buyers.get(buyer).current_ix
@@ -331,34 +2087,74 @@ impl WebGum for Contract {
 
     #[storage(read)]
     fn get_bought_project(buyer: Identity, index: u64) -> Project {
-        let project_id = storage.buyers.get(buyer).get(index);
-        storage.project_listings.get(project_id)
+        get_existing_project(storage.buyers.get(buyer).get(index))
+    }
+
+    #[storage(read)]
+    fn try_get_bought_project(buyer: Identity, index: u64) -> Option<Project> {
+        match storage.buyers.get(buyer).try_get(index) {
+            Option::Some(project_id) => Option::Some(get_existing_project(project_id)),
+            Option::None => Option::None,
+        }
     }
 
     #[storage(read)]
     fn has_bought_project(project_id: u64, wallet: Identity) -> bool {
-        let mut existing: Vector = storage.buyers.get(wallet);
+        has_access(project_id, wallet)
+    }
 
-        let mut i = 0;
-        while i < existing.current_ix {
-            let project = existing.get(i);
-            if project == project_id {
-                return true;
-            }
+    #[storage(read)]
+    fn get_purchase_count() -> u64 {
+        storage.purchase_count
+    }
+
+    #[storage(read)]
+    fn get_purchase(purchase_id: u64) -> Purchase {
+        require(purchase_id < storage.purchase_count, InvalidError::PurchaseNotFound);
+        storage.purchase_receipts.get(purchase_id)
+    }
+
+    #[storage(read)]
+    fn get_purchase_by_buyer(project_id: u64, buyer: Identity) -> Option<Purchase> {
+        match storage.purchases.get((buyer, project_id)) {
+            Option::Some(purchase_id) => Option::Some(storage.purchase_receipts.get(purchase_id)),
+            Option::None => Option::None,
+        }
+    }
+
+    #[storage(read)]
+    fn get_projects(start: u64, count: u64) -> ProjectPage {
+        let count = page_count(count);
+        let mut ids = Vector::new();
+        let mut i = start;
+        while i < storage.project_count && i - start < count {
+            ids.push(i);
             i += 1;
         }
+        get_project_page(ids)
+    }
 
-        return false;
+    #[storage(read)]
+    fn get_projects_by_ids(project_ids: Vec<u64>) -> ProjectPage {
+        require(project_ids.len() <= PAGE_SIZE, InvalidError::PageTooLarge);
+
+        let mut ids = Vector::new();
+        let mut i = 0;
+        while i < project_ids.len() {
+            ids.push(project_ids.get(i).unwrap());
+            i += 1;
+        }
+        get_project_page(ids)
     }
 
     #[storage(read)]
-    fn get_creator_vector(id: Identity) -> Vector {
-        storage.creators.get(id)
+    fn get_created_projects(creator: Identity, start: u64, count: u64) -> ProjectPage {
+        get_project_page(slice(storage.creators.get(creator), start, count))
     }
 
     #[storage(read)]
-    fn get_buyer_vector(id: Identity) -> Vector {
-        storage.buyers.get(id)
+    fn get_bought_projects(buyer: Identity, start: u64, count: u64) -> ProjectPage {
+        get_project_page(slice(storage.buyers.get(buyer), start, count))
     }
 
     #[storage(read)]
@@ -368,6 +2164,7 @@ impl WebGum for Contract {
 
     #[storage(read)]
     fn get_project_rating(index: u64) -> (Identity, u64) {
+        require(!storage.removed_reviews.get(index), InvalidError::ReviewNotFound);
         let rating_result = storage.ratings.get(index);
         let rating_tuple = match rating_result {
             Option::Some(rating_result) => rating_result,
@@ -376,6 +2173,203 @@ impl WebGum for Contract {
         (rating_tuple.1, rating_tuple.2)
     }
 
+    #[storage(read)]
+    fn get_referral_rate(project_id: u64) -> u64 {
+        storage.referral_rates.get(project_id)
+    }
+
+    #[storage(read)]
+    fn get_referral_sales(referrer: Identity) -> u64 {
+        storage.referral_sales.get(referrer)
+    }
+
+    #[storage(read)]
+    fn get_referral_earnings(referrer: Identity) -> u64 {
+        storage.referral_earnings.get(referrer)
+    }
+
+    #[storage(read)]
+    fn get_royalty_rate(project_id: u64) -> u64 {
+        storage.royalty_rates.get(project_id)
+    }
+
+    #[storage(read)]
+    fn get_resale_price(project_id: u64, seller: Identity) -> Option<u64> {
+        storage.resale_listings.get((project_id, seller))
+    }
+
+    #[storage(read)]
+    fn get_metadata_version_count(project_id: u64) -> u64 {
+        storage.metadata_version_count.get(project_id)
+    }
+
+    #[storage(read)]
+    fn get_metadata_version(project_id: u64, version: u64) -> MetadataVersion {
+        require(version < storage.metadata_version_count.get(project_id), InvalidError::VersionNotFound);
+        storage.metadata_versions.get((project_id, version))
+    }
+
+    #[storage(read)]
+    fn get_purchased_version(project_id: u64, buyer: Identity) -> Option<u64> {
+        storage.purchased_versions.get((project_id, buyer))
+    }
+
+    #[storage(read)]
+    fn get_encryption_key(wallet: Identity) -> Option<b256> {
+        storage.encryption_keys.get(wallet)
+    }
+
+    #[storage(read)]
+    fn get_delivered_key(project_id: u64, buyer: Identity) -> Option<KeyDelivery> {
+        let project: Project = get_existing_project(project_id);
+
+        // the key is encrypted, this just keeps other callers from fetching it
+        let sender: Result<Identity, AuthError> = msg_sender();
+        let is_buyer = sender.unwrap() == buyer && has_access(project_id, buyer);
+        require(is_buyer || sender.unwrap() == project.owner_address, InvalidError::CantFetchKey);
+
+        storage.delivered_keys.get((project_id, buyer))
+    }
+
+    #[storage(read)]
+    fn get_project_buyer(project_id: u64, index: u64) -> Identity {
+        let project: Project = get_existing_project(project_id);
+        require(index < project.buyer_count, InvalidError::BuyerNotFound);
+        storage.project_buyers.get((project_id, index))
+    }
+
+    #[storage(read)]
+    fn get_project_buyers(project_id: u64, start: u64) -> BuyerPage {
+        let project: Project = get_existing_project(project_id);
+
+        let mut count = 0;
+        if start < project.buyer_count {
+            count = project.buyer_count - start;
+        }
+        if count > PAGE_SIZE {
+            count = PAGE_SIZE;
+        }
+
+        BuyerPage {
+            buyers: [
+                get_page_buyer(project_id, start, count, 0),
+                get_page_buyer(project_id, start, count, 1),
+                get_page_buyer(project_id, start, count, 2),
+                get_page_buyer(project_id, start, count, 3),
+                get_page_buyer(project_id, start, count, 4),
+            ],
+            count: count,
+        }
+    }
+
+    #[storage(read, write)]
+    fn add_category(name_hash: b256) -> u64 {
+        require_owner();
+
+        let category = storage.category_count + 1;
+        storage.categories.insert(category, name_hash);
+        storage.category_count = category;
+        category
+    }
+
+    #[storage(read)]
+    fn get_category_count() -> u64 {
+        storage.category_count
+    }
+
+    #[storage(read)]
+    fn get_category(category: u64) -> b256 {
+        require(category > 0 && category <= storage.category_count, InvalidError::InvalidCategory);
+        storage.categories.get(category)
+    }
+
+    #[storage(read)]
+    fn get_category_project_count(category: u64) -> u64 {
+        storage.category_project_count.get(category)
+    }
+
+    #[storage(read)]
+    fn get_category_project(category: u64, index: u64) -> Project {
+        require(index < storage.category_project_count.get(category), InvalidError::ProjectNotFoundInCategory);
+        let project_id = storage.category_projects.get((category, index));
+        get_existing_project(project_id)
+    }
+
+    #[storage(read)]
+    fn get_category_project_ids(category: u64, start: u64) -> Vector {
+        let count = storage.category_project_count.get(category);
+
+        let mut page = Vector::new();
+        let mut i = start;
+        while i < count && i - start < PAGE_SIZE {
+            page.push(storage.category_projects.get((category, i)));
+            i += 1;
+        }
+        page
+    }
+
+    #[storage(read)]
+    fn get_dutch_auction(project_id: u64) -> Option<DutchAuction> {
+        storage.dutch_auctions.get(project_id)
+    }
+
+    #[storage(read)]
+    fn get_current_price(project_id: u64) -> u64 {
+        current_price(project_id)
+    }
+
+    #[storage(read)]
+    fn get_sealed_auction(project_id: u64) -> Option<SealedAuction> {
+        storage.sealed_auctions.get(project_id)
+    }
+
+    #[storage(read)]
+    fn get_sealed_bid(project_id: u64, bidder: Identity) -> Option<SealedBid> {
+        storage.sealed_bids.get((project_id, bidder))
+    }
+
+    #[storage(read)]
+    fn get_bundle(bundle_id: u64) -> Option<Bundle> {
+        storage.bundles.get(bundle_id)
+    }
+
+    #[storage(read)]
+    fn get_project_tips(project_id: u64) -> u64 {
+        storage.project_tips.get(project_id)
+    }
+
+    #[storage(read)]
+    fn is_blocked(wallet: Identity) -> bool {
+        storage.blocked.get(wallet)
+    }
+
+    #[storage(read)]
+    fn get_hidden_reason(project_id: u64) -> Option<u64> {
+        storage.hidden_projects.get(project_id)
+    }
+
+    #[storage(read)]
+    fn get_presale(project_id: u64) -> Option<Presale> {
+        storage.presales.get(project_id)
+    }
+
+    #[storage(read)]
+    fn get_marketplace_stats() -> MarketplaceStats {
+        MarketplaceStats {
+            project_count: storage.project_count,
+            purchase_count: storage.purchase_count - storage.refunded_purchase_count,
+            review_count: storage.ratings.len() - storage.removed_review_count,
+            buyer_count: storage.unique_buyer_count,
+            creator_count: storage.unique_creator_count,
+            volume: storage.volume.get(BASE_ASSET_ID),
+        }
+    }
+
+    #[storage(read)]
+    fn get_asset_volume(asset_id: ContractId) -> u64 {
+        storage.volume.get(asset_id)
+    }
+
     #[storage(read, write)]
     fn initialize_owner() -> Identity {
         let owner = storage.owner;
@@ -391,19 +2385,81 @@ impl WebGum for Contract {
 
     #[storage(read)]
     fn withdraw_funds() {
-        let owner = storage.owner;
-        // make sure the owner has been initialized
-        require(owner.is_some(), InvalidError::OwnerNotInitialized);
-        let sender: Result<Identity, AuthError> = msg_sender(); 
-        // require the sender to be the owner
-        require(sender.unwrap() == owner.unwrap(), InvalidError::OnlyOwner);
+        require_owner();
 
-        // get the current balance of this contract for the base asset
-        let amount = this_balance(BASE_ASSET_ID);
+        // get the current balance of this contract for the base asset, minus escrowed funds
+        let amount = this_balance(BASE_ASSET_ID) - storage.escrow_balance;
 
         // require the contract balance to be more than 0
         require(amount > 0, InvalidError::NotEnoughTokens);
         // send the amount to the owner
-        transfer(amount, BASE_ASSET_ID, owner.unwrap());
+        transfer(amount, BASE_ASSET_ID, storage.owner.unwrap());
+    }
+
+    #[storage(read, write)]
+    fn set_tip_commission(enabled: bool) {
+        require_owner();
+        storage.tip_commission = enabled;
+    }
+
+    #[storage(read, write)]
+    fn hide_project(project_id: u64, reason_code: u64) {
+        require_owner();
+        let _project: Project = get_existing_project(project_id);
+
+        storage.hidden_projects.insert(project_id, Option::Some(reason_code));
+        log(ProjectHiddenEvent {
+            project_id: project_id,
+            reason_code: reason_code,
+        });
+    }
+
+    #[storage(read, write)]
+    fn unhide_project(project_id: u64) {
+        require_owner();
+        require(is_hidden(project_id), InvalidError::ProjectNotHidden);
+
+        storage.hidden_projects.insert(project_id, Option::None);
+        log(ProjectUnhiddenEvent {
+            project_id: project_id,
+        });
+    }
+
+    #[storage(read, write)]
+    fn remove_review(review_id: u64) {
+        require_owner();
+        // review ids are the index in ratings + 1, the same as in ratings_map
+        require(review_id > 0, InvalidError::ReviewNotFound);
+        let index = review_id - 1;
+        let rating = storage.ratings.get(index);
+        require(rating.is_some(), InvalidError::ReviewNotFound);
+        require(!storage.removed_reviews.get(index), InvalidError::ReviewNotFound);
+        let rating_tuple = rating.unwrap();
+        let project_id = rating_tuple.0;
+
+        storage.removed_reviews.insert(index, true);
+        storage.removed_review_count = storage.removed_review_count + 1;
+
+        let mut existing: Vector = storage.ratings_map.get(project_id);
+        existing.remove(review_id);
+        storage.ratings_map.insert(project_id, existing);
+
+        log(ReviewRemovedEvent {
+            review_id: review_id,
+            project_id: project_id,
+            reviewer: rating_tuple.1,
+        });
+    }
+
+    #[storage(read, write)]
+    fn block_identity(wallet: Identity) {
+        require_owner();
+        storage.blocked.insert(wallet, true);
+    }
+
+    #[storage(read, write)]
+    fn unblock_identity(wallet: Identity) {
+        require_owner();
+        storage.blocked.insert(wallet, false);
     }
 }
@@ -1,8 +1,14 @@
 use fuels::{
+    core::types::Bits256,
     prelude::*,
+    signers::fuel_crypto::Hasher,
     tx::{AssetId, ContractId},
 };
 
+mod utils;
+
+use utils::{arweave_id_to_metadata, cid_to_metadata, metadata_to_string};
+
 // Load abi from json
 abigen!(MyContract, "out/debug/webgum-contract-abi.json");
 
@@ -63,17 +69,14 @@ async fn can_list_buy_and_review_projects() {
     let wallet_3_id = Identity::Address(wallet_3_address);
 
     // project1 params
-    let metadata: fuels::core::types::SizedAsciiString<59> =
-        "bafybeib2skplfyirumj5fhxptybx7b7ryv3xntpu2oqn47pusykbzpalzq"
-            .try_into()
-            .expect("Should have succeeded");
+    let metadata = cid_to_metadata("bafybeib2skplfyirumj5fhxptybx7b7ryv3xntpu2oqn47pusykbzpalzq");
     let price: u64 = 10;
     let max_buyers: u64 = 3;
 
     // make a project
     let project1 = instance
         .methods()
-        .list_project(price, max_buyers, metadata)
+        .list_project(price, max_buyers, metadata, 0, [0; 3])
         .call()
         .await
         .unwrap();
@@ -89,29 +92,26 @@ async fn can_list_buy_and_review_projects() {
     assert!(project1_copy.value.price == price);
     // println!("Project 1 created: {:?}", project1.value);
 
-    let creator_vector_1 = instance
+    let creator_page_1 = instance
         .methods()
-        .get_creator_vector(wallet_1_id.clone())
+        .get_created_projects(wallet_1_id.clone(), 0, 5)
         .call()
         .await
         .unwrap();
-    // println!("Creator Vector 1: {:?}", creator_vector_1.value);
-    assert!(creator_vector_1.value.current_ix == 1);
+    // println!("Creator Page 1: {:?}", creator_page_1.value);
+    assert!(creator_page_1.value.count == 1);
 
-    let creator_vector_3 = instance
+    let creator_page_3 = instance
         .methods()
-        .get_creator_vector(wallet_3_id.clone())
+        .get_created_projects(wallet_3_id.clone(), 0, 5)
         .call()
         .await
         .unwrap();
-    // println!("Creator Vector 3 BEFORE: {:?}", creator_vector_3.value);
-    assert!(creator_vector_3.value.current_ix == 0);
+    // println!("Creator Page 3 BEFORE: {:?}", creator_page_3.value);
+    assert!(creator_page_3.value.count == 0);
 
     // project2 params
-    let metadata2: fuels::core::types::SizedAsciiString<59> =
-        "bafybeibqatmbzqdg6dhpjz6mep2vcaxoxahccrmgz5d5te3fzcmjkxszwy"
-            .try_into()
-            .expect("Should have succeeded");
+    let metadata2 = cid_to_metadata("bafybeibqatmbzqdg6dhpjz6mep2vcaxoxahccrmgz5d5te3fzcmjkxszwy");
     let price2: u64 = 33;
     let max_buyers2: u64 = 0;
 
@@ -120,7 +120,7 @@ async fn can_list_buy_and_review_projects() {
         .with_wallet(wallet_3.clone())
         .unwrap()
         .methods()
-        .list_project(price2, max_buyers2, metadata2.clone())
+        .list_project(price2, max_buyers2, metadata2.clone(), 0, [0; 3])
         .call()
         .await
         .unwrap();
@@ -137,10 +137,7 @@ async fn can_list_buy_and_review_projects() {
     assert!(project2.value.metadata == metadata2);
 
     // project3 params
-    let metadata3: fuels::core::types::SizedAsciiString<59> =
-        "bafybeihd6wnw4wodno2ew5grxluto5jnzki6pscr6mr57pgntnc7fx5c4a"
-            .try_into()
-            .expect("Should have succeeded");
+    let metadata3 = cid_to_metadata("bafybeihd6wnw4wodno2ew5grxluto5jnzki6pscr6mr57pgntnc7fx5c4a");
     let price3: u64 = 50;
     let max_buyers3: u64 = 1000;
 
@@ -149,7 +146,7 @@ async fn can_list_buy_and_review_projects() {
         .with_wallet(wallet_3.clone())
         .unwrap()
         .methods()
-        .list_project(price3, max_buyers3, metadata3.clone())
+        .list_project(price3, max_buyers3, metadata3.clone(), 0, [0; 3])
         .call()
         .await
         .unwrap();
@@ -165,16 +162,16 @@ async fn can_list_buy_and_review_projects() {
     // println!("TOTAL PROJECTS {:?}", total.value);
     assert!(total.value == 3);
 
-    let creator_vector_3_copy = instance
+    let creator_page_3_copy = instance
         .methods()
-        .get_creator_vector(wallet_3_id.clone())
+        .get_created_projects(wallet_3_id.clone(), 0, 5)
         .call()
         .await
         .unwrap();
-    // println!("Creator Vector 3 AFTER: {:?}", creator_vector_3_copy.value);
-    assert!(creator_vector_3_copy.value.inner[0] == 1);
-    assert!(creator_vector_3_copy.value.inner[1] == 2);
-    assert!(creator_vector_3_copy.value.current_ix == 2);
+    // println!("Creator Page 3 AFTER: {:?}", creator_page_3_copy.value);
+    assert!(creator_page_3_copy.value.projects[0].project_id == 1);
+    assert!(creator_page_3_copy.value.projects[1].project_id == 2);
+    assert!(creator_page_3_copy.value.count == 2);
 
     // check if creator list was updated
     let creator_list_length = instance
@@ -351,10 +348,7 @@ async fn can_list_buy_and_review_projects() {
     assert!(project1_copy2.value.buyer_count == 2);
 
     // new project2 params
-    let new_metadata2: fuels::core::types::SizedAsciiString<59> =
-        "bafybeih65sh4qmpkm7a3ib3o7romb23gqdlzvtqw4jgk2tnf6c66nqitci"
-            .try_into()
-            .expect("Should have succeeded");
+    let new_metadata2 = cid_to_metadata("bafybeih65sh4qmpkm7a3ib3o7romb23gqdlzvtqw4jgk2tnf6c66nqitci");
     let new_price2: u64 = 55;
     let new_max_buyers2: u64 = 11;
 
@@ -363,7 +357,7 @@ async fn can_list_buy_and_review_projects() {
         .with_wallet(wallet_3.clone())
         .unwrap()
         .methods()
-        .update_project(1, new_price2, new_max_buyers2, new_metadata2.clone())
+        .update_project(1, new_price2, new_max_buyers2, new_metadata2.clone(), None, 0, [0; 3])
         .call()
         .await
         .unwrap();
@@ -423,17 +417,14 @@ async fn can_set_owner_and_withdraw_funds() {
         .await;
 
     // project1 params
-    let metadata: fuels::core::types::SizedAsciiString<59> =
-        "bafybeib2skplfyirumj5fhxptybx7b7ryv3xntpu2oqn47pusykbzpalzq"
-            .try_into()
-            .expect("Should have succeeded");
+    let metadata = cid_to_metadata("bafybeib2skplfyirumj5fhxptybx7b7ryv3xntpu2oqn47pusykbzpalzq");
     let price: u64 = 15_000;
     let max_buyers: u64 = 3;
 
     // make a project
     let project1 = instance
         .methods()
-        .list_project(price, max_buyers, metadata)
+        .list_project(price, max_buyers, metadata, 0, [0; 3])
         .call()
         .await
         .unwrap();
@@ -449,29 +440,26 @@ async fn can_set_owner_and_withdraw_funds() {
     assert!(project1_copy.value.price == price);
     // println!("Project 1 created: {:?}", project1.value);
 
-    let creator_vector_1 = instance
+    let creator_page_1 = instance
         .methods()
-        .get_creator_vector(wallet_1_id.clone())
+        .get_created_projects(wallet_1_id.clone(), 0, 5)
         .call()
         .await
         .unwrap();
-    // println!("Creator Vector 1: {:?}", creator_vector_1.value);
-    assert!(creator_vector_1.value.current_ix == 1);
+    // println!("Creator Page 1: {:?}", creator_page_1.value);
+    assert!(creator_page_1.value.count == 1);
 
-    let creator_vector_3 = instance
+    let creator_page_3 = instance
         .methods()
-        .get_creator_vector(wallet_3_id.clone())
+        .get_created_projects(wallet_3_id.clone(), 0, 5)
         .call()
         .await
         .unwrap();
-    // println!("Creator Vector 3 BEFORE: {:?}", creator_vector_3.value);
-    assert!(creator_vector_3.value.current_ix == 0);
+    // println!("Creator Page 3 BEFORE: {:?}", creator_page_3.value);
+    assert!(creator_page_3.value.count == 0);
 
     // project2 params
-    let metadata2: fuels::core::types::SizedAsciiString<59> =
-        "bafybeibqatmbzqdg6dhpjz6mep2vcaxoxahccrmgz5d5te3fzcmjkxszwy"
-            .try_into()
-            .expect("Should have succeeded");
+    let metadata2 = cid_to_metadata("bafybeibqatmbzqdg6dhpjz6mep2vcaxoxahccrmgz5d5te3fzcmjkxszwy");
     let price2: u64 = 33_000;
     let max_buyers2: u64 = 0;
 
@@ -480,7 +468,7 @@ async fn can_set_owner_and_withdraw_funds() {
         .with_wallet(wallet_3.clone())
         .unwrap()
         .methods()
-        .list_project(price2, max_buyers2, metadata2.clone())
+        .list_project(price2, max_buyers2, metadata2.clone(), 0, [0; 3])
         .call()
         .await
         .unwrap();
@@ -497,10 +485,7 @@ async fn can_set_owner_and_withdraw_funds() {
     assert!(project2.value.metadata == metadata2);
 
     // project3 params
-    let metadata3: fuels::core::types::SizedAsciiString<59> =
-        "bafybeihd6wnw4wodno2ew5grxluto5jnzki6pscr6mr57pgntnc7fx5c4a"
-            .try_into()
-            .expect("Should have succeeded");
+    let metadata3 = cid_to_metadata("bafybeihd6wnw4wodno2ew5grxluto5jnzki6pscr6mr57pgntnc7fx5c4a");
     let price3: u64 = 50_000;
     let max_buyers3: u64 = 1000;
 
@@ -509,7 +494,7 @@ async fn can_set_owner_and_withdraw_funds() {
         .with_wallet(wallet_3.clone())
         .unwrap()
         .methods()
-        .list_project(price3, max_buyers3, metadata3.clone())
+        .list_project(price3, max_buyers3, metadata3.clone(), 0, [0; 3])
         .call()
         .await
         .unwrap();
@@ -525,16 +510,16 @@ async fn can_set_owner_and_withdraw_funds() {
     // println!("TOTAL PROJECTS {:?}", total.value);
     assert!(total.value == 3);
 
-    let creator_vector_3_copy = instance
+    let creator_page_3_copy = instance
         .methods()
-        .get_creator_vector(wallet_3_id.clone())
+        .get_created_projects(wallet_3_id.clone(), 0, 5)
         .call()
         .await
         .unwrap();
-    // println!("Creator Vector 3 AFTER: {:?}", creator_vector_3_copy.value);
-    assert!(creator_vector_3_copy.value.inner[0] == 1);
-    assert!(creator_vector_3_copy.value.inner[1] == 2);
-    assert!(creator_vector_3_copy.value.current_ix == 2);
+    // println!("Creator Page 3 AFTER: {:?}", creator_page_3_copy.value);
+    assert!(creator_page_3_copy.value.projects[0].project_id == 1);
+    assert!(creator_page_3_copy.value.projects[1].project_id == 2);
+    assert!(creator_page_3_copy.value.count == 2);
 
     // check if creator list was updated
     let creator_list_length = instance
@@ -680,3 +665,2893 @@ async fn can_set_owner_and_withdraw_funds() {
     assert!(balance_3 == 999983350);
     assert!(balance_4 == 1000003150);
 }
+
+#[tokio::test]
+async fn can_buy_project_with_referrer() {
+    let (instance, _id, wallets) = get_contract_instance().await;
+
+    let wallet_1 = wallets.get(0).unwrap();
+    let wallet_2 = wallets.get(1).unwrap();
+    let wallet_3 = wallets.get(2).unwrap();
+    let wallet_4 = wallets.get(3).unwrap();
+
+    let wallet_1_address: Address = wallet_1.clone().address().into();
+    let wallet_1_id = Identity::Address(wallet_1_address);
+
+    let wallet_2_address: Address = wallet_2.clone().address().into();
+    let wallet_2_id = Identity::Address(wallet_2_address);
+
+    let wallet_3_address: Address = wallet_3.clone().address().into();
+    let wallet_3_id = Identity::Address(wallet_3_address);
+
+    // project1 params
+    let metadata = cid_to_metadata("bafybeib2skplfyirumj5fhxptybx7b7ryv3xntpu2oqn47pusykbzpalzq");
+    let price: u64 = 10_000;
+    let max_buyers: u64 = 0;
+
+    // make a project from wallet_1
+    let _project1 = instance
+        .methods()
+        .list_project(price, max_buyers, metadata, 0, [0; 3])
+        .call()
+        .await
+        .unwrap();
+
+    // referral rates over 100% are rejected
+    let bad_rate = instance.methods().set_referral_rate(0, 101).call().await;
+    assert!(bad_rate.is_err());
+
+    // only the creator can set the referral rate
+    let not_owner = instance
+        .with_wallet(wallet_2.clone())
+        .unwrap()
+        .methods()
+        .set_referral_rate(0, 20)
+        .call()
+        .await;
+    assert!(not_owner.is_err());
+
+    // pay referrers 20% of the creator's share
+    let _rate_resp = instance
+        .methods()
+        .set_referral_rate(0, 20)
+        .call()
+        .await
+        .unwrap();
+
+    const BASE_ASSET_ID: AssetId = AssetId::new([0u8; 32]);
+
+    // wallet_2 can't refer themself
+    let call_params = CallParameters::new(Some(price), Some(BASE_ASSET_ID), None);
+    let self_referral = instance
+        .with_wallet(wallet_2.clone())
+        .unwrap()
+        .methods()
+        .buy_project_with_referrer(0, wallet_2_id.clone())
+        .append_variable_outputs(2)
+        .call_params(call_params)
+        .call()
+        .await;
+    assert!(self_referral.is_err());
+
+    // the creator can't be the referrer
+    let call_params = CallParameters::new(Some(price), Some(BASE_ASSET_ID), None);
+    let owner_referral = instance
+        .with_wallet(wallet_2.clone())
+        .unwrap()
+        .methods()
+        .buy_project_with_referrer(0, wallet_1_id.clone())
+        .append_variable_outputs(2)
+        .call_params(call_params)
+        .call()
+        .await;
+    assert!(owner_referral.is_err());
+
+    // buy project 0 from wallet_2, referred by wallet_3
+    let call_params = CallParameters::new(Some(price), Some(BASE_ASSET_ID), None);
+    let _resp = instance
+        .with_wallet(wallet_2.clone())
+        .unwrap()
+        .methods()
+        .buy_project_with_referrer(0, wallet_3_id.clone())
+        .append_variable_outputs(2)
+        .call_params(call_params)
+        .call()
+        .await
+        .unwrap();
+
+    let referral_sales = instance
+        .methods()
+        .get_referral_sales(wallet_3_id.clone())
+        .call()
+        .await
+        .unwrap();
+    assert!(referral_sales.value == 1);
+
+    // 10_000 - 500 commission = 9_500, 20% of which goes to the referrer
+    let referral_earnings = instance
+        .methods()
+        .get_referral_earnings(wallet_3_id.clone())
+        .call()
+        .await
+        .unwrap();
+    assert!(referral_earnings.value == 1_900);
+
+    let has_project = instance
+        .methods()
+        .has_bought_project(0, wallet_2_id.clone())
+        .call()
+        .await
+        .unwrap();
+    assert!(has_project.value == true);
+
+    let balance_1: u64 = wallet_1.get_asset_balance(&BASE_ASSET_ID).await.unwrap();
+    let balance_2: u64 = wallet_2.get_asset_balance(&BASE_ASSET_ID).await.unwrap();
+    let balance_3: u64 = wallet_3.get_asset_balance(&BASE_ASSET_ID).await.unwrap();
+
+    assert!(balance_1 == 1000007600);
+    assert!(balance_2 == 999990000);
+    assert!(balance_3 == 1000001900);
+
+    // the project keeps the full amount paid and the commission
+    let project1 = instance.methods().get_project(0).call().await.unwrap();
+    assert!(project1.value.total_revenue == 10_000);
+    assert!(project1.value.total_commission == 500);
+
+    // the creator earned the payout minus the referral
+    let creator_earnings = instance
+        .methods()
+        .get_creator_earnings(wallet_1_id.clone())
+        .call()
+        .await
+        .unwrap();
+    assert!(creator_earnings.value == 7_600);
+
+    // a 100% referral rate pays the whole creator's share to the referrer
+    let metadata = cid_to_metadata("bafybeib2skplfyirumj5fhxptybx7b7ryv3xntpu2oqn47pusykbzpalzq");
+    let _project2 = instance
+        .methods()
+        .list_project(1_000, max_buyers, metadata, 0, [0; 3])
+        .call()
+        .await
+        .unwrap();
+
+    let _full_rate = instance
+        .methods()
+        .set_referral_rate(1, 100)
+        .call()
+        .await
+        .unwrap();
+
+    let call_params = CallParameters::new(Some(1_000), Some(BASE_ASSET_ID), None);
+    let _full_referral = instance
+        .with_wallet(wallet_4.clone())
+        .unwrap()
+        .methods()
+        .buy_project_with_referrer(1, wallet_3_id.clone())
+        .append_variable_outputs(1)
+        .call_params(call_params)
+        .call()
+        .await
+        .unwrap();
+
+    let balance_1: u64 = wallet_1.get_asset_balance(&BASE_ASSET_ID).await.unwrap();
+    let balance_3: u64 = wallet_3.get_asset_balance(&BASE_ASSET_ID).await.unwrap();
+
+    assert!(balance_1 == 1000007600);
+    assert!(balance_3 == 1000002900);
+}
+
+#[tokio::test]
+async fn can_buy_project_as_gift() {
+    let (instance, _id, wallets) = get_contract_instance().await;
+
+    let wallet_2 = wallets.get(1).unwrap();
+    let wallet_3 = wallets.get(2).unwrap();
+    let wallet_4 = wallets.get(3).unwrap();
+
+    let wallet_2_address: Address = wallet_2.clone().address().into();
+    let wallet_2_id = Identity::Address(wallet_2_address);
+
+    let wallet_3_address: Address = wallet_3.clone().address().into();
+    let wallet_3_id = Identity::Address(wallet_3_address);
+
+    let wallet_4_address: Address = wallet_4.clone().address().into();
+    let wallet_4_id = Identity::Address(wallet_4_address);
+
+    // project1 params
+    let metadata = cid_to_metadata("bafybeib2skplfyirumj5fhxptybx7b7ryv3xntpu2oqn47pusykbzpalzq");
+    let price: u64 = 10;
+    let max_buyers: u64 = 1;
+
+    // make a project with a single seat
+    let _project1 = instance
+        .methods()
+        .list_project(price, max_buyers, metadata, 0, [0; 3])
+        .call()
+        .await
+        .unwrap();
+
+    const BASE_ASSET_ID: AssetId = AssetId::new([0u8; 32]);
+
+    // wallet_2 buys project 0 for wallet_3
+    let call_params = CallParameters::new(Some(price), Some(BASE_ASSET_ID), None);
+    let _resp = instance
+        .with_wallet(wallet_2.clone())
+        .unwrap()
+        .methods()
+        .buy_project_for(0, wallet_3_id.clone())
+        .append_variable_outputs(1)
+        .call_params(call_params)
+        .call()
+        .await
+        .unwrap();
+
+    // the recipient has access, the gifter doesn't
+    let recipient_has_project = instance
+        .methods()
+        .has_bought_project(0, wallet_3_id.clone())
+        .call()
+        .await
+        .unwrap();
+    assert!(recipient_has_project.value == true);
+
+    let gifter_has_project = instance
+        .methods()
+        .has_bought_project(0, wallet_2_id.clone())
+        .call()
+        .await
+        .unwrap();
+    assert!(gifter_has_project.value == false);
+
+    // the recipient can review the project
+    let _review = instance
+        .with_wallet(wallet_3.clone())
+        .unwrap()
+        .methods()
+        .review_project(0, 5)
+        .call()
+        .await
+        .unwrap();
+
+    // wallet_3 can't be gifted the same project twice
+    let call_params = CallParameters::new(Some(price), Some(BASE_ASSET_ID), None);
+    let duplicate = instance
+        .with_wallet(wallet_2.clone())
+        .unwrap()
+        .methods()
+        .buy_project_for(0, wallet_3_id.clone())
+        .append_variable_outputs(1)
+        .call_params(call_params)
+        .call()
+        .await;
+    assert!(duplicate.is_err());
+
+    // the only seat is taken
+    let call_params = CallParameters::new(Some(price), Some(BASE_ASSET_ID), None);
+    let sold_out = instance
+        .with_wallet(wallet_2.clone())
+        .unwrap()
+        .methods()
+        .buy_project_for(0, wallet_4_id.clone())
+        .append_variable_outputs(1)
+        .call_params(call_params)
+        .call()
+        .await;
+    assert!(sold_out.is_err());
+
+    let balance_2: u64 = wallet_2.get_asset_balance(&BASE_ASSET_ID).await.unwrap();
+    let balance_3: u64 = wallet_3.get_asset_balance(&BASE_ASSET_ID).await.unwrap();
+
+    assert!(balance_2 == 999999990);
+    assert!(balance_3 == 1000000000);
+}
+
+#[tokio::test]
+async fn can_buy_projects_in_one_cart() {
+    let (instance, _id, wallets) = get_contract_instance().await;
+
+    let wallet_1 = wallets.get(0).unwrap();
+    let wallet_2 = wallets.get(1).unwrap();
+    let wallet_3 = wallets.get(2).unwrap();
+
+    let wallet_3_address: Address = wallet_3.clone().address().into();
+    let wallet_3_id = Identity::Address(wallet_3_address);
+
+    let metadata = cid_to_metadata("bafybeib2skplfyirumj5fhxptybx7b7ryv3xntpu2oqn47pusykbzpalzq");
+
+    // project 0: 2_000 with unlimited buyers
+    let _project1 = instance
+        .methods()
+        .list_project(2_000, 0, metadata.clone(), 0, [0; 3])
+        .call()
+        .await
+        .unwrap();
+
+    // project 1: 50 with a single seat
+    let _project2 = instance
+        .methods()
+        .list_project(50, 1, metadata.clone(), 0, [0; 3])
+        .call()
+        .await
+        .unwrap();
+
+    // project 2: 30 with unlimited buyers
+    let _project3 = instance
+        .methods()
+        .list_project(30, 0, metadata.clone(), 0, [0; 3])
+        .call()
+        .await
+        .unwrap();
+
+    const BASE_ASSET_ID: AssetId = AssetId::new([0u8; 32]);
+
+    // wallet_2 takes the only seat of project 1
+    let call_params = CallParameters::new(Some(50), Some(BASE_ASSET_ID), None);
+    let _resp = instance
+        .with_wallet(wallet_2.clone())
+        .unwrap()
+        .methods()
+        .buy_project(1)
+        .append_variable_outputs(1)
+        .call_params(call_params)
+        .call()
+        .await
+        .unwrap();
+
+    // a cart with the sold out project reverts entirely
+    let call_params = CallParameters::new(Some(2_080), Some(BASE_ASSET_ID), None);
+    let sold_out_cart = instance
+        .with_wallet(wallet_3.clone())
+        .unwrap()
+        .methods()
+        .buy_projects(vec![0, 1, 2])
+        .append_variable_outputs(3)
+        .call_params(call_params)
+        .call()
+        .await;
+    assert!(sold_out_cart.is_err());
+
+    let project1 = instance.methods().get_project(0).call().await.unwrap();
+    assert!(project1.value.buyer_count == 0);
+
+    // underpaying for the cart reverts
+    let call_params = CallParameters::new(Some(2_029), Some(BASE_ASSET_ID), None);
+    let underpaid_cart = instance
+        .with_wallet(wallet_3.clone())
+        .unwrap()
+        .methods()
+        .buy_projects(vec![0, 2])
+        .append_variable_outputs(3)
+        .call_params(call_params)
+        .call()
+        .await;
+    assert!(underpaid_cart.is_err());
+
+    // buy projects 0 and 2 together, overpaying by 10
+    let call_params = CallParameters::new(Some(2_040), Some(BASE_ASSET_ID), None);
+    let _cart = instance
+        .with_wallet(wallet_3.clone())
+        .unwrap()
+        .methods()
+        .buy_projects(vec![0, 2])
+        .append_variable_outputs(3)
+        .call_params(call_params)
+        .call()
+        .await
+        .unwrap();
+
+    let buyer_list_length = instance
+        .methods()
+        .get_buyer_list_length(wallet_3_id.clone())
+        .call()
+        .await
+        .unwrap();
+    assert!(buyer_list_length.value == 2);
+
+    let project1 = instance.methods().get_project(0).call().await.unwrap();
+    assert!(project1.value.buyer_count == 1);
+
+    let project3 = instance.methods().get_project(2).call().await.unwrap();
+    assert!(project3.value.buyer_count == 1);
+
+    let balance_1: u64 = wallet_1.get_asset_balance(&BASE_ASSET_ID).await.unwrap();
+    let balance_3: u64 = wallet_3.get_asset_balance(&BASE_ASSET_ID).await.unwrap();
+
+    // 50 + (2_000 - 100 commission) + 30
+    assert!(balance_1 == 1000001980);
+    // the extra 10 is sent back
+    assert!(balance_3 == 999997970);
+}
+
+#[tokio::test]
+async fn can_resell_license_with_royalty() {
+    let (instance, _id, wallets) = get_contract_instance().await;
+
+    let wallet_1 = wallets.get(0).unwrap();
+    let wallet_2 = wallets.get(1).unwrap();
+    let wallet_3 = wallets.get(2).unwrap();
+    let wallet_4 = wallets.get(3).unwrap();
+
+    let wallet_2_address: Address = wallet_2.clone().address().into();
+    let wallet_2_id = Identity::Address(wallet_2_address);
+
+    let wallet_3_address: Address = wallet_3.clone().address().into();
+    let wallet_3_id = Identity::Address(wallet_3_address);
+
+    let metadata = cid_to_metadata("bafybeib2skplfyirumj5fhxptybx7b7ryv3xntpu2oqn47pusykbzpalzq");
+    let price: u64 = 100;
+    let max_buyers: u64 = 1;
+
+    // make a project with a single seat and a 10% royalty
+    let _project1 = instance
+        .methods()
+        .list_project(price, max_buyers, metadata, 0, [0; 3])
+        .call()
+        .await
+        .unwrap();
+
+    let _royalty = instance
+        .methods()
+        .set_royalty_rate(0, 10)
+        .call()
+        .await
+        .unwrap();
+
+    const BASE_ASSET_ID: AssetId = AssetId::new([0u8; 32]);
+
+    // wallet_3 can't list a license they don't hold
+    let not_holder = instance
+        .with_wallet(wallet_3.clone())
+        .unwrap()
+        .methods()
+        .list_license_for_resale(0, 500)
+        .call()
+        .await;
+    assert!(not_holder.is_err());
+
+    // wallet_2 buys the only seat
+    let call_params = CallParameters::new(Some(price), Some(BASE_ASSET_ID), None);
+    let _resp = instance
+        .with_wallet(wallet_2.clone())
+        .unwrap()
+        .methods()
+        .buy_project(0)
+        .append_variable_outputs(1)
+        .call_params(call_params)
+        .call()
+        .await
+        .unwrap();
+
+    // the license can't be bought before it is listed
+    let call_params = CallParameters::new(Some(500), Some(BASE_ASSET_ID), None);
+    let not_listed = instance
+        .with_wallet(wallet_3.clone())
+        .unwrap()
+        .methods()
+        .buy_resale(0, wallet_2_id.clone())
+        .append_variable_outputs(2)
+        .call_params(call_params)
+        .call()
+        .await;
+    assert!(not_listed.is_err());
+
+    // wallet_2 lists the license for 500
+    let _listing = instance
+        .with_wallet(wallet_2.clone())
+        .unwrap()
+        .methods()
+        .list_license_for_resale(0, 500)
+        .call()
+        .await
+        .unwrap();
+
+    let resale_price = instance
+        .methods()
+        .get_resale_price(0, wallet_2_id.clone())
+        .call()
+        .await
+        .unwrap();
+    assert!(resale_price.value == Some(500));
+
+    // wallet_3 buys the license from wallet_2
+    let call_params = CallParameters::new(Some(500), Some(BASE_ASSET_ID), None);
+    let _resale = instance
+        .with_wallet(wallet_3.clone())
+        .unwrap()
+        .methods()
+        .buy_resale(0, wallet_2_id.clone())
+        .append_variable_outputs(2)
+        .call_params(call_params)
+        .call()
+        .await
+        .unwrap();
+
+    let seller_has_project = instance
+        .methods()
+        .has_bought_project(0, wallet_2_id.clone())
+        .call()
+        .await
+        .unwrap();
+    assert!(seller_has_project.value == false);
+
+    let buyer_has_project = instance
+        .methods()
+        .has_bought_project(0, wallet_3_id.clone())
+        .call()
+        .await
+        .unwrap();
+    assert!(buyer_has_project.value == true);
+
+    // the listing is cleared and the seat count is unchanged
+    let resale_price = instance
+        .methods()
+        .get_resale_price(0, wallet_2_id.clone())
+        .call()
+        .await
+        .unwrap();
+    assert!(resale_price.value == None);
+
+    let project1 = instance.methods().get_project(0).call().await.unwrap();
+    assert!(project1.value.buyer_count == 1);
+
+    // wallet_3 takes wallet_2's place in the project's buyer list
+    let project_buyer = instance
+        .methods()
+        .get_project_buyer(0, 0)
+        .call()
+        .await
+        .unwrap();
+    assert!(project_buyer.value == wallet_3_id);
+
+    let balance_1: u64 = wallet_1.get_asset_balance(&BASE_ASSET_ID).await.unwrap();
+    let balance_2: u64 = wallet_2.get_asset_balance(&BASE_ASSET_ID).await.unwrap();
+    let balance_3: u64 = wallet_3.get_asset_balance(&BASE_ASSET_ID).await.unwrap();
+
+    // 100 sale + 50 royalty
+    assert!(balance_1 == 1000000150);
+    // -100 purchase + 450 resale
+    assert!(balance_2 == 1000000350);
+    assert!(balance_3 == 999999500);
+
+    // resales add royalties to the creator's earnings, not to the project's revenue
+    let project1 = instance.methods().get_project(0).call().await.unwrap();
+    assert!(project1.value.total_revenue == 100);
+
+    let wallet_1_address: Address = wallet_1.clone().address().into();
+    let creator_earnings = instance
+        .methods()
+        .get_creator_earnings(Identity::Address(wallet_1_address))
+        .call()
+        .await
+        .unwrap();
+    assert!(creator_earnings.value == 150);
+
+    // a 100% royalty pays the whole resale to the creator
+    let _full_royalty = instance
+        .methods()
+        .set_royalty_rate(0, 100)
+        .call()
+        .await
+        .unwrap();
+
+    let _listing = instance
+        .with_wallet(wallet_3.clone())
+        .unwrap()
+        .methods()
+        .list_license_for_resale(0, 200)
+        .call()
+        .await
+        .unwrap();
+
+    let call_params = CallParameters::new(Some(200), Some(BASE_ASSET_ID), None);
+    let _full_resale = instance
+        .with_wallet(wallet_4.clone())
+        .unwrap()
+        .methods()
+        .buy_resale(0, wallet_3_id.clone())
+        .append_variable_outputs(1)
+        .call_params(call_params)
+        .call()
+        .await
+        .unwrap();
+
+    let balance_1: u64 = wallet_1.get_asset_balance(&BASE_ASSET_ID).await.unwrap();
+    let balance_3: u64 = wallet_3.get_asset_balance(&BASE_ASSET_ID).await.unwrap();
+
+    assert!(balance_1 == 1000000350);
+    assert!(balance_3 == 999999500);
+}
+
+#[tokio::test]
+async fn can_store_any_content_id_as_metadata() {
+    let (instance, _id, _wallets) = get_contract_instance().await;
+
+    let content_ids = [
+        // CIDv1 dag-pb in base32
+        "bafybeib2skplfyirumj5fhxptybx7b7ryv3xntpu2oqn47pusykbzpalzq",
+        // CIDv1 raw in base32
+        "bafkreihdwdcefgh4dqkjv67uzcmw7ojee6xedzdetojuzjevtenxquvyku",
+        // CIDv0
+        "QmYwAPJzv5CZsnA625s3Xf2nemtYgPpHdWEz79ojWnPbdG",
+    ];
+
+    for (project_id, content_id) in content_ids.iter().enumerate() {
+        let _project = instance
+            .methods()
+            .list_project(10, 0, cid_to_metadata(content_id), 0, [0; 3])
+            .call()
+            .await
+            .unwrap();
+
+        let project = instance
+            .methods()
+            .get_project(project_id as u64)
+            .call()
+            .await
+            .unwrap();
+        assert!(metadata_to_string(&project.value.metadata) == *content_id);
+    }
+
+    // Arweave transaction id
+    let arweave_id = "bNbA3TEQVL60xlgCcqdz4ZPHFZ711cZ3hmkpGttDt_U";
+    let _project = instance
+        .methods()
+        .list_project(10, 0, arweave_id_to_metadata(arweave_id), 0, [0; 3])
+        .call()
+        .await
+        .unwrap();
+
+    let project = instance.methods().get_project(3).call().await.unwrap();
+    assert!(metadata_to_string(&project.value.metadata) == arweave_id);
+}
+
+#[tokio::test]
+async fn can_track_metadata_versions() {
+    let (instance, _id, wallets) = get_contract_instance().await;
+
+    let wallet_2 = wallets.get(1).unwrap();
+    let wallet_3 = wallets.get(2).unwrap();
+
+    let wallet_2_address: Address = wallet_2.clone().address().into();
+    let wallet_2_id = Identity::Address(wallet_2_address);
+
+    let wallet_3_address: Address = wallet_3.clone().address().into();
+    let wallet_3_id = Identity::Address(wallet_3_address);
+
+    let metadata = cid_to_metadata("bafybeib2skplfyirumj5fhxptybx7b7ryv3xntpu2oqn47pusykbzpalzq");
+    let price: u64 = 10;
+
+    let _project1 = instance
+        .methods()
+        .list_project(price, 0, metadata.clone(), 0, [0; 3])
+        .call()
+        .await
+        .unwrap();
+
+    const BASE_ASSET_ID: AssetId = AssetId::new([0u8; 32]);
+
+    // wallet_2 buys version 0
+    let call_params = CallParameters::new(Some(price), Some(BASE_ASSET_ID), None);
+    let _resp = instance
+        .with_wallet(wallet_2.clone())
+        .unwrap()
+        .methods()
+        .buy_project(0)
+        .append_variable_outputs(1)
+        .call_params(call_params)
+        .call()
+        .await
+        .unwrap();
+
+    // changing only the price doesn't add a version
+    let _updated = instance
+        .methods()
+        .update_project(0, price, 0, metadata.clone(), None, 0, [0; 3])
+        .call()
+        .await
+        .unwrap();
+
+    let version_count = instance
+        .methods()
+        .get_metadata_version_count(0)
+        .call()
+        .await
+        .unwrap();
+    assert!(version_count.value == 1);
+
+    // publish version 1 with a changelog
+    let new_metadata = cid_to_metadata("bafybeih65sh4qmpkm7a3ib3o7romb23gqdlzvtqw4jgk2tnf6c66nqitci");
+    let changelog = cid_to_metadata("bafkreihdwdcefgh4dqkjv67uzcmw7ojee6xedzdetojuzjevtenxquvyku");
+    let _updated = instance
+        .methods()
+        .update_project(0, price, 0, new_metadata.clone(), Some(changelog.clone()), 0, [0; 3])
+        .call()
+        .await
+        .unwrap();
+
+    let version_count = instance
+        .methods()
+        .get_metadata_version_count(0)
+        .call()
+        .await
+        .unwrap();
+    assert!(version_count.value == 2);
+
+    let version_0 = instance
+        .methods()
+        .get_metadata_version(0, 0)
+        .call()
+        .await
+        .unwrap();
+    assert!(version_0.value.metadata == metadata);
+    assert!(version_0.value.changelog == None);
+
+    let version_1 = instance
+        .methods()
+        .get_metadata_version(0, 1)
+        .call()
+        .await
+        .unwrap();
+    assert!(version_1.value.metadata == new_metadata);
+    assert!(version_1.value.changelog == Some(changelog));
+    assert!(version_1.value.block_height >= version_0.value.block_height);
+
+    // there is no version 2
+    let missing_version = instance.methods().get_metadata_version(0, 2).call().await;
+    assert!(missing_version.is_err());
+
+    // wallet_3 buys version 1
+    let call_params = CallParameters::new(Some(price), Some(BASE_ASSET_ID), None);
+    let _resp = instance
+        .with_wallet(wallet_3.clone())
+        .unwrap()
+        .methods()
+        .buy_project(0)
+        .append_variable_outputs(1)
+        .call_params(call_params)
+        .call()
+        .await
+        .unwrap();
+
+    let purchased_version_2 = instance
+        .methods()
+        .get_purchased_version(0, wallet_2_id.clone())
+        .call()
+        .await
+        .unwrap();
+    assert!(purchased_version_2.value == Some(0));
+
+    let purchased_version_3 = instance
+        .methods()
+        .get_purchased_version(0, wallet_3_id.clone())
+        .call()
+        .await
+        .unwrap();
+    assert!(purchased_version_3.value == Some(1));
+}
+
+#[tokio::test]
+async fn can_deliver_encrypted_keys() {
+    let (instance, _id, wallets) = get_contract_instance().await;
+
+    let wallet_2 = wallets.get(1).unwrap();
+    let wallet_3 = wallets.get(2).unwrap();
+
+    let wallet_2_address: Address = wallet_2.clone().address().into();
+    let wallet_2_id = Identity::Address(wallet_2_address);
+
+    let metadata = cid_to_metadata("bafybeib2skplfyirumj5fhxptybx7b7ryv3xntpu2oqn47pusykbzpalzq");
+    let price: u64 = 10;
+
+    let _project1 = instance
+        .methods()
+        .list_project(price, 0, metadata, 0, [0; 3])
+        .call()
+        .await
+        .unwrap();
+
+    const BASE_ASSET_ID: AssetId = AssetId::new([0u8; 32]);
+
+    let call_params = CallParameters::new(Some(price), Some(BASE_ASSET_ID), None);
+    let _resp = instance
+        .with_wallet(wallet_2.clone())
+        .unwrap()
+        .methods()
+        .buy_project(0)
+        .append_variable_outputs(1)
+        .call_params(call_params)
+        .call()
+        .await
+        .unwrap();
+
+    let encrypted_key = [Bits256([1; 32]), Bits256([2; 32]), Bits256([3; 32])];
+
+    // the buyer hasn't registered a public key yet
+    let not_registered = instance
+        .methods()
+        .deliver_key(0, wallet_2_id.clone(), encrypted_key.clone())
+        .call()
+        .await;
+    assert!(not_registered.is_err());
+
+    let encryption_key = Bits256([7; 32]);
+    let _register = instance
+        .with_wallet(wallet_2.clone())
+        .unwrap()
+        .methods()
+        .register_encryption_key(encryption_key)
+        .call()
+        .await
+        .unwrap();
+
+    // only the creator can deliver keys
+    let not_owner = instance
+        .with_wallet(wallet_2.clone())
+        .unwrap()
+        .methods()
+        .deliver_key(0, wallet_2_id.clone(), encrypted_key.clone())
+        .call()
+        .await;
+    assert!(not_owner.is_err());
+
+    let _deliver = instance
+        .methods()
+        .deliver_key(0, wallet_2_id.clone(), encrypted_key.clone())
+        .call()
+        .await
+        .unwrap();
+
+    // the buyer gets the key back
+    let delivered_key = instance
+        .with_wallet(wallet_2.clone())
+        .unwrap()
+        .methods()
+        .get_delivered_key(0, wallet_2_id.clone())
+        .call()
+        .await
+        .unwrap();
+    let delivery = delivered_key.value.unwrap();
+    assert!(delivery.encryption_key == encryption_key);
+    assert!(delivery.encrypted_key == encrypted_key);
+
+    // so does the creator
+    let creator_copy = instance
+        .methods()
+        .get_delivered_key(0, wallet_2_id.clone())
+        .call()
+        .await
+        .unwrap();
+    assert!(creator_copy.value.unwrap() == delivery);
+
+    // but no one else
+    let other_wallet = instance
+        .with_wallet(wallet_3.clone())
+        .unwrap()
+        .methods()
+        .get_delivered_key(0, wallet_2_id.clone())
+        .call()
+        .await;
+    assert!(other_wallet.is_err());
+}
+
+#[tokio::test]
+async fn can_enumerate_project_buyers() {
+    let (instance, _id, wallets) = get_contract_instance().await;
+
+    let metadata = cid_to_metadata("bafybeib2skplfyirumj5fhxptybx7b7ryv3xntpu2oqn47pusykbzpalzq");
+    let price: u64 = 10;
+
+    let _project1 = instance
+        .methods()
+        .list_project(price, 0, metadata, 0, [0; 3])
+        .call()
+        .await
+        .unwrap();
+
+    const BASE_ASSET_ID: AssetId = AssetId::new([0u8; 32]);
+
+    // every wallet but the creator buys project 0
+    let mut buyer_ids = vec![];
+    for wallet in wallets.iter().skip(1) {
+        let call_params = CallParameters::new(Some(price), Some(BASE_ASSET_ID), None);
+        let _resp = instance
+            .with_wallet(wallet.clone())
+            .unwrap()
+            .methods()
+            .buy_project(0)
+            .append_variable_outputs(1)
+            .call_params(call_params)
+            .call()
+            .await
+            .unwrap();
+
+        let wallet_address: Address = wallet.clone().address().into();
+        buyer_ids.push(Identity::Address(wallet_address));
+    }
+
+    for (index, buyer_id) in buyer_ids.iter().enumerate() {
+        let project_buyer = instance
+            .methods()
+            .get_project_buyer(0, index as u64)
+            .call()
+            .await
+            .unwrap();
+        assert!(project_buyer.value == *buyer_id);
+    }
+
+    // there are only 3 buyers
+    let out_of_range = instance.methods().get_project_buyer(0, 3).call().await;
+    assert!(out_of_range.is_err());
+
+    // page through the buyers starting at the second one
+    let page = instance
+        .methods()
+        .get_project_buyers(0, 1)
+        .call()
+        .await
+        .unwrap();
+    assert!(page.value.count == 2);
+    assert!(page.value.buyers[0] == buyer_ids[1]);
+    assert!(page.value.buyers[1] == buyer_ids[2]);
+
+    let empty_page = instance
+        .methods()
+        .get_project_buyers(0, 3)
+        .call()
+        .await
+        .unwrap();
+    assert!(empty_page.value.count == 0);
+    // a start near u64::MAX returns an empty page instead of reverting
+    let far_page = instance
+        .methods()
+        .get_project_buyers(0, u64::MAX)
+        .call()
+        .await
+        .unwrap();
+    assert!(far_page.value.count == 0);
+}
+
+#[tokio::test]
+async fn can_set_creator_profile() {
+    let (instance, _id, wallets) = get_contract_instance().await;
+
+    let wallet_1 = wallets.get(0).unwrap();
+    let wallet_2 = wallets.get(1).unwrap();
+
+    let wallet_1_address: Address = wallet_1.clone().address().into();
+    let wallet_1_id = Identity::Address(wallet_1_address);
+
+    // no profile yet
+    let creator = instance
+        .methods()
+        .get_creator(wallet_1_id.clone())
+        .call()
+        .await
+        .unwrap();
+    assert!(creator.value.profile == None);
+    assert!(creator.value.project_count == 0);
+
+    let display_name_hash = Bits256([9; 32]);
+    let profile = cid_to_metadata("bafkreihdwdcefgh4dqkjv67uzcmw7ojee6xedzdetojuzjevtenxquvyku");
+    let _profile_resp = instance
+        .methods()
+        .set_creator_profile(display_name_hash, profile.clone())
+        .call()
+        .await
+        .unwrap();
+
+    let metadata = cid_to_metadata("bafybeib2skplfyirumj5fhxptybx7b7ryv3xntpu2oqn47pusykbzpalzq");
+    let price: u64 = 10;
+
+    let _project1 = instance
+        .methods()
+        .list_project(price, 0, metadata, 0, [0; 3])
+        .call()
+        .await
+        .unwrap();
+
+    const BASE_ASSET_ID: AssetId = AssetId::new([0u8; 32]);
+
+    let call_params = CallParameters::new(Some(price), Some(BASE_ASSET_ID), None);
+    let _resp = instance
+        .with_wallet(wallet_2.clone())
+        .unwrap()
+        .methods()
+        .buy_project(0)
+        .append_variable_outputs(1)
+        .call_params(call_params)
+        .call()
+        .await
+        .unwrap();
+
+    let creator = instance
+        .methods()
+        .get_creator(wallet_1_id.clone())
+        .call()
+        .await
+        .unwrap();
+    let creator_profile = creator.value.profile.unwrap();
+    assert!(creator_profile.display_name_hash == display_name_hash);
+    assert!(creator_profile.profile == profile);
+    assert!(creator.value.project_count == 1);
+    assert!(creator.value.sales == 1);
+}
+
+#[tokio::test]
+async fn can_filter_projects_by_category() {
+    let (instance, _id, wallets) = get_contract_instance().await;
+
+    let wallet_2 = wallets.get(1).unwrap();
+
+    let _initialize_resp = instance.methods().initialize_owner().call().await.unwrap();
+
+    // only the contract owner can add categories
+    let not_owner = instance
+        .with_wallet(wallet_2.clone())
+        .unwrap()
+        .methods()
+        .add_category(Bits256([1; 32]))
+        .call()
+        .await;
+    assert!(not_owner.is_err());
+
+    let category_1 = instance
+        .methods()
+        .add_category(Bits256([1; 32]))
+        .call()
+        .await
+        .unwrap();
+    assert!(category_1.value == 1);
+
+    let category_2 = instance
+        .methods()
+        .add_category(Bits256([2; 32]))
+        .call()
+        .await
+        .unwrap();
+    assert!(category_2.value == 2);
+
+    let category_name = instance.methods().get_category(2).call().await.unwrap();
+    assert!(category_name.value == Bits256([2; 32]));
+
+    let metadata = cid_to_metadata("bafybeib2skplfyirumj5fhxptybx7b7ryv3xntpu2oqn47pusykbzpalzq");
+
+    // category 3 doesn't exist
+    let invalid_category = instance
+        .methods()
+        .list_project(10, 0, metadata.clone(), 3, [0; 3])
+        .call()
+        .await;
+    assert!(invalid_category.is_err());
+
+    // list projects 0 and 1 in category 1, project 2 uncategorized
+    let project1 = instance
+        .methods()
+        .list_project(10, 0, metadata.clone(), 1, [4, 5, 0])
+        .call()
+        .await
+        .unwrap();
+    assert!(project1.value.category == 1);
+    assert!(project1.value.tags == [4, 5, 0]);
+
+    let _project2 = instance
+        .methods()
+        .list_project(20, 0, metadata.clone(), 1, [0; 3])
+        .call()
+        .await
+        .unwrap();
+
+    let _project3 = instance
+        .methods()
+        .list_project(30, 0, metadata.clone(), 0, [0; 3])
+        .call()
+        .await
+        .unwrap();
+
+    let category_ids = instance
+        .methods()
+        .get_category_project_ids(1, 0)
+        .call()
+        .await
+        .unwrap();
+    assert!(category_ids.value.current_ix == 2);
+    assert!(category_ids.value.inner[0] == 0);
+    assert!(category_ids.value.inner[1] == 1);
+
+    let far_ids = instance
+        .methods()
+        .get_category_project_ids(1, u64::MAX)
+        .call()
+        .await
+        .unwrap();
+    assert!(far_ids.value.current_ix == 0);
+
+    // move project 0 to category 2
+    let _updated = instance
+        .methods()
+        .update_project(0, 10, 0, metadata.clone(), None, 2, [6, 0, 0])
+        .call()
+        .await
+        .unwrap();
+
+    let category_1_count = instance
+        .methods()
+        .get_category_project_count(1)
+        .call()
+        .await
+        .unwrap();
+    assert!(category_1_count.value == 1);
+
+    let category_1_project = instance
+        .methods()
+        .get_category_project(1, 0)
+        .call()
+        .await
+        .unwrap();
+    assert!(category_1_project.value.project_id == 1);
+
+    let category_2_project = instance
+        .methods()
+        .get_category_project(2, 0)
+        .call()
+        .await
+        .unwrap();
+    assert!(category_2_project.value.project_id == 0);
+    assert!(category_2_project.value.tags == [6, 0, 0]);
+
+    // category 1 only has 1 project left
+    let out_of_range = instance.methods().get_category_project(1, 1).call().await;
+    assert!(out_of_range.is_err());
+}
+
+#[tokio::test]
+async fn can_page_through_projects() {
+    let (instance, _id, wallets) = get_contract_instance().await;
+
+    let wallet_2 = wallets.get(1).unwrap();
+
+    let wallet_2_address: Address = wallet_2.clone().address().into();
+    let wallet_2_id = Identity::Address(wallet_2_address);
+
+    let metadata = cid_to_metadata("bafybeib2skplfyirumj5fhxptybx7b7ryv3xntpu2oqn47pusykbzpalzq");
+
+    // list 7 projects priced 1 to 7
+    for price in 1..8 {
+        let _project = instance
+            .methods()
+            .list_project(price, 0, metadata.clone(), 0, [0; 3])
+            .call()
+            .await
+            .unwrap();
+    }
+
+    // pages are capped at 5 projects
+    let first_page = instance.methods().get_projects(0, 10).call().await.unwrap();
+    assert!(first_page.value.count == 5);
+    assert!(first_page.value.projects[4].price == 5);
+
+    let last_page = instance.methods().get_projects(5, 5).call().await.unwrap();
+    assert!(last_page.value.count == 2);
+    assert!(last_page.value.projects[0].project_id == 5);
+    assert!(last_page.value.projects[1].project_id == 6);
+
+    // asking for as many as allowed, or starting far past the end, doesn't overflow
+    let max_page = instance.methods().get_projects(0, u64::MAX).call().await.unwrap();
+    assert!(max_page.value.count == 5);
+
+    let far_page = instance
+        .methods()
+        .get_projects(u64::MAX, u64::MAX)
+        .call()
+        .await
+        .unwrap();
+    assert!(far_page.value.count == 0);
+
+    let by_ids = instance
+        .methods()
+        .get_projects_by_ids(vec![6, 2])
+        .call()
+        .await
+        .unwrap();
+    assert!(by_ids.value.count == 2);
+    assert!(by_ids.value.projects[0].price == 7);
+    assert!(by_ids.value.projects[1].price == 3);
+
+    let too_many_ids = instance
+        .methods()
+        .get_projects_by_ids(vec![0, 1, 2, 3, 4, 5])
+        .call()
+        .await;
+    assert!(too_many_ids.is_err());
+
+    const BASE_ASSET_ID: AssetId = AssetId::new([0u8; 32]);
+
+    // wallet_2 buys projects 1, 3 and 5
+    let call_params = CallParameters::new(Some(12), Some(BASE_ASSET_ID), None);
+    let _cart = instance
+        .with_wallet(wallet_2.clone())
+        .unwrap()
+        .methods()
+        .buy_projects(vec![1, 3, 5])
+        .append_variable_outputs(3)
+        .call_params(call_params)
+        .call()
+        .await
+        .unwrap();
+
+    let bought_page = instance
+        .methods()
+        .get_bought_projects(wallet_2_id.clone(), 1, 5)
+        .call()
+        .await
+        .unwrap();
+    assert!(bought_page.value.count == 2);
+    assert!(bought_page.value.projects[0].project_id == 3);
+    assert!(bought_page.value.projects[1].project_id == 5);
+
+    let max_bought_page = instance
+        .methods()
+        .get_bought_projects(wallet_2_id.clone(), 0, u64::MAX)
+        .call()
+        .await
+        .unwrap();
+    assert!(max_bought_page.value.count == 3);
+}
+
+#[tokio::test]
+async fn rejects_unknown_project_ids() {
+    let (instance, _id, wallets) = get_contract_instance().await;
+
+    let wallet_2 = wallets.get(1).unwrap();
+
+    let metadata = cid_to_metadata("bafybeib2skplfyirumj5fhxptybx7b7ryv3xntpu2oqn47pusykbzpalzq");
+
+    let _project1 = instance
+        .methods()
+        .list_project(10, 0, metadata.clone(), 0, [0; 3])
+        .call()
+        .await
+        .unwrap();
+
+    // project 1 doesn't exist
+    let missing_project = instance.methods().get_project(1).call().await;
+    assert!(missing_project.is_err());
+
+    let try_missing_project = instance.methods().try_get_project(1).call().await.unwrap();
+    assert!(try_missing_project.value == None);
+
+    let try_project = instance.methods().try_get_project(0).call().await.unwrap();
+    assert!(try_project.value.unwrap().price == 10);
+
+    let update_missing = instance
+        .methods()
+        .update_project(1, 10, 0, metadata.clone(), None, 0, [0; 3])
+        .call()
+        .await;
+    assert!(update_missing.is_err());
+
+    const BASE_ASSET_ID: AssetId = AssetId::new([0u8; 32]);
+
+    // a zero amount call can't buy a project that doesn't exist
+    let call_params = CallParameters::new(Some(0), Some(BASE_ASSET_ID), None);
+    let buy_missing = instance
+        .with_wallet(wallet_2.clone())
+        .unwrap()
+        .methods()
+        .buy_project(1)
+        .append_variable_outputs(1)
+        .call_params(call_params)
+        .call()
+        .await;
+    assert!(buy_missing.is_err());
+
+    let missing_in_batch = instance
+        .methods()
+        .get_projects_by_ids(vec![0, 1])
+        .call()
+        .await;
+    assert!(missing_in_batch.is_err());
+}
+
+#[tokio::test]
+async fn rejects_out_of_range_indexes() {
+    let (instance, _id, wallets) = get_contract_instance().await;
+
+    let wallet_1 = wallets.get(0).unwrap();
+    let wallet_2 = wallets.get(1).unwrap();
+
+    let wallet_1_address: Address = wallet_1.clone().address().into();
+    let wallet_1_id = Identity::Address(wallet_1_address);
+
+    let wallet_2_address: Address = wallet_2.clone().address().into();
+    let wallet_2_id = Identity::Address(wallet_2_address);
+
+    let metadata = cid_to_metadata("bafybeib2skplfyirumj5fhxptybx7b7ryv3xntpu2oqn47pusykbzpalzq");
+
+    let _project1 = instance
+        .methods()
+        .list_project(10, 0, metadata, 0, [0; 3])
+        .call()
+        .await
+        .unwrap();
+
+    // wallet_1 has created a single project
+    let created_project = instance
+        .methods()
+        .get_created_project(wallet_1_id.clone(), 3)
+        .call()
+        .await;
+    assert!(created_project.is_err());
+
+    let created_project_id = instance
+        .methods()
+        .get_created_project_id(wallet_1_id.clone(), 1)
+        .call()
+        .await;
+    assert!(created_project_id.is_err());
+
+    let try_created_project = instance
+        .methods()
+        .try_get_created_project(wallet_1_id.clone(), 3)
+        .call()
+        .await
+        .unwrap();
+    assert!(try_created_project.value == None);
+
+    let try_created_project_id = instance
+        .methods()
+        .try_get_created_project_id(wallet_1_id.clone(), 0)
+        .call()
+        .await
+        .unwrap();
+    assert!(try_created_project_id.value == Some(0));
+
+    // wallet_2 hasn't bought anything
+    let bought_project = instance
+        .methods()
+        .get_bought_project(wallet_2_id.clone(), 0)
+        .call()
+        .await;
+    assert!(bought_project.is_err());
+
+    let try_bought_project = instance
+        .methods()
+        .try_get_bought_project(wallet_2_id.clone(), 0)
+        .call()
+        .await
+        .unwrap();
+    assert!(try_bought_project.value == None);
+}
+
+#[tokio::test]
+async fn can_get_purchase_receipts() {
+    let (instance, _id, wallets) = get_contract_instance().await;
+
+    let wallet_2 = wallets.get(1).unwrap();
+    let wallet_3 = wallets.get(2).unwrap();
+
+    let wallet_2_address: Address = wallet_2.clone().address().into();
+    let wallet_2_id = Identity::Address(wallet_2_address);
+
+    let wallet_3_address: Address = wallet_3.clone().address().into();
+    let wallet_3_id = Identity::Address(wallet_3_address);
+
+    let metadata = cid_to_metadata("bafybeib2skplfyirumj5fhxptybx7b7ryv3xntpu2oqn47pusykbzpalzq");
+
+    let _project1 = instance
+        .methods()
+        .list_project(10, 0, metadata, 0, [0; 3])
+        .call()
+        .await
+        .unwrap();
+
+    const BASE_ASSET_ID: AssetId = AssetId::new([0u8; 32]);
+
+    // wallet_2 pays 15 for project 0
+    let call_params = CallParameters::new(Some(15), Some(BASE_ASSET_ID), None);
+    let _resp = instance
+        .with_wallet(wallet_2.clone())
+        .unwrap()
+        .methods()
+        .buy_project(0)
+        .append_variable_outputs(1)
+        .call_params(call_params)
+        .call()
+        .await
+        .unwrap();
+
+    let purchase_count = instance.methods().get_purchase_count().call().await.unwrap();
+    assert!(purchase_count.value == 1);
+
+    let purchase = instance.methods().get_purchase(0).call().await.unwrap();
+    assert!(purchase.value.purchase_id == 0);
+    assert!(purchase.value.buyer == wallet_2_id);
+    assert!(purchase.value.project_id == 0);
+    assert!(purchase.value.asset_id == ContractId::from(*BASE_ASSET_ID));
+    assert!(purchase.value.amount == 15);
+    assert!(purchase.value.commission == 0);
+    assert!(purchase.value.block_height > 0);
+
+    let buyer_purchase = instance
+        .methods()
+        .get_purchase_by_buyer(0, wallet_2_id.clone())
+        .call()
+        .await
+        .unwrap();
+    assert!(buyer_purchase.value.unwrap() == purchase.value);
+
+    let no_purchase = instance
+        .methods()
+        .get_purchase_by_buyer(0, wallet_3_id.clone())
+        .call()
+        .await
+        .unwrap();
+    assert!(no_purchase.value == None);
+
+    let missing_purchase = instance.methods().get_purchase(1).call().await;
+    assert!(missing_purchase.is_err());
+}
+
+#[tokio::test]
+async fn can_get_marketplace_stats() {
+    let (instance, _id, wallets) = get_contract_instance().await;
+
+    let wallet_2 = wallets.get(1).unwrap();
+    let wallet_3 = wallets.get(2).unwrap();
+
+    let metadata = cid_to_metadata("bafybeib2skplfyirumj5fhxptybx7b7ryv3xntpu2oqn47pusykbzpalzq");
+
+    // wallet_1 lists 2 projects, wallet_2 lists 1
+    let _project1 = instance
+        .methods()
+        .list_project(10, 0, metadata.clone(), 0, [0; 3])
+        .call()
+        .await
+        .unwrap();
+
+    let _project2 = instance
+        .methods()
+        .list_project(20, 0, metadata.clone(), 0, [0; 3])
+        .call()
+        .await
+        .unwrap();
+
+    let _project3 = instance
+        .with_wallet(wallet_2.clone())
+        .unwrap()
+        .methods()
+        .list_project(30, 0, metadata.clone(), 0, [0; 3])
+        .call()
+        .await
+        .unwrap();
+
+    const BASE_ASSET_ID: AssetId = AssetId::new([0u8; 32]);
+
+    // wallet_3 buys projects 0 and 2, wallet_2 buys project 1
+    let call_params = CallParameters::new(Some(40), Some(BASE_ASSET_ID), None);
+    let _cart = instance
+        .with_wallet(wallet_3.clone())
+        .unwrap()
+        .methods()
+        .buy_projects(vec![0, 2])
+        .append_variable_outputs(2)
+        .call_params(call_params)
+        .call()
+        .await
+        .unwrap();
+
+    let call_params = CallParameters::new(Some(20), Some(BASE_ASSET_ID), None);
+    let _resp = instance
+        .with_wallet(wallet_2.clone())
+        .unwrap()
+        .methods()
+        .buy_project(1)
+        .append_variable_outputs(1)
+        .call_params(call_params)
+        .call()
+        .await
+        .unwrap();
+
+    let _review = instance
+        .with_wallet(wallet_3.clone())
+        .unwrap()
+        .methods()
+        .review_project(0, 5)
+        .call()
+        .await
+        .unwrap();
+
+    let stats = instance.methods().get_marketplace_stats().call().await.unwrap();
+    assert!(stats.value.project_count == 3);
+    assert!(stats.value.purchase_count == 3);
+    assert!(stats.value.review_count == 1);
+    assert!(stats.value.buyer_count == 2);
+    assert!(stats.value.creator_count == 2);
+    assert!(stats.value.volume == 60);
+
+    let base_asset_volume = instance
+        .methods()
+        .get_asset_volume(ContractId::from(*BASE_ASSET_ID))
+        .call()
+        .await
+        .unwrap();
+    assert!(base_asset_volume.value == 60);
+}
+
+#[tokio::test]
+async fn can_price_project_with_dutch_auction() {
+    let (instance, _id, wallets) = get_contract_instance().await;
+
+    let wallet_1 = wallets.get(0).unwrap();
+    let wallet_2 = wallets.get(1).unwrap();
+
+    let wallet_2_address: Address = wallet_2.clone().address().into();
+    let wallet_2_id = Identity::Address(wallet_2_address);
+
+    let provider = wallet_1.get_provider().unwrap();
+
+    let metadata = cid_to_metadata("bafybeib2skplfyirumj5fhxptybx7b7ryv3xntpu2oqn47pusykbzpalzq");
+
+    let _project1 = instance
+        .methods()
+        .list_project(5_000, 1, metadata, 0, [0; 3])
+        .call()
+        .await
+        .unwrap();
+
+    let start_price: u64 = 10_000;
+    let floor_price: u64 = 1_000;
+    let start_block = provider.latest_block_height().await.unwrap() + 10;
+    let end_block = start_block + 100;
+
+    // the floor can't be above the start price
+    let invalid_auction = instance
+        .methods()
+        .start_dutch_auction(0, floor_price, start_price, start_block, end_block)
+        .call()
+        .await;
+    assert!(invalid_auction.is_err());
+
+    let _auction = instance
+        .methods()
+        .start_dutch_auction(0, start_price, floor_price, start_block, end_block)
+        .call()
+        .await
+        .unwrap();
+
+    // the price drops by 90 every block
+    let expected_price = |block: u64| -> u64 {
+        if block <= start_block {
+            start_price
+        } else if block >= end_block {
+            floor_price
+        } else {
+            start_price - 90 * (block - start_block)
+        }
+    };
+
+    let before_start = instance.methods().get_current_price(0).call().await.unwrap();
+    assert!(before_start.value == start_price);
+
+    provider.produce_blocks(60, None).await.unwrap();
+
+    // the call may run at the latest or the next block height
+    let block = provider.latest_block_height().await.unwrap();
+    let mid_auction = instance.methods().get_current_price(0).call().await.unwrap();
+    assert!(mid_auction.value == expected_price(block) || mid_auction.value == expected_price(block + 1));
+    assert!(mid_auction.value < start_price && mid_auction.value > floor_price);
+
+    const BASE_ASSET_ID: AssetId = AssetId::new([0u8; 32]);
+
+    // pay the start price, the rest of the payment is refunded
+    let call_params = CallParameters::new(Some(start_price), Some(BASE_ASSET_ID), None);
+    let _resp = instance
+        .with_wallet(wallet_2.clone())
+        .unwrap()
+        .methods()
+        .buy_project(0)
+        .append_variable_outputs(2)
+        .call_params(call_params)
+        .call()
+        .await
+        .unwrap();
+
+    let purchase = instance
+        .methods()
+        .get_purchase_by_buyer(0, wallet_2_id.clone())
+        .call()
+        .await
+        .unwrap();
+    let purchase = purchase.value.unwrap();
+    assert!(purchase.amount == expected_price(purchase.block_height));
+    assert!(purchase.amount <= mid_auction.value);
+
+    let balance_2: u64 = wallet_2.get_asset_balance(&BASE_ASSET_ID).await.unwrap();
+    assert!(balance_2 == 1_000_000_000 - purchase.amount);
+
+    // after the end block the price stays at the floor
+    provider.produce_blocks(100, None).await.unwrap();
+
+    let after_end = instance.methods().get_current_price(0).call().await.unwrap();
+    assert!(after_end.value == floor_price);
+
+    // ending the auction goes back to the listed price
+    let _end = instance
+        .methods()
+        .end_dutch_auction(0)
+        .call()
+        .await
+        .unwrap();
+
+    let listed_price = instance.methods().get_current_price(0).call().await.unwrap();
+    assert!(listed_price.value == 5_000);
+}
+
+#[tokio::test]
+async fn can_sell_one_of_one_by_sealed_bids() {
+    let (instance, _id, wallets) = get_contract_instance().await;
+
+    let wallet_1 = wallets.get(0).unwrap();
+    let wallet_2 = wallets.get(1).unwrap();
+    let wallet_3 = wallets.get(2).unwrap();
+
+    let wallet_2_address: Address = wallet_2.clone().address().into();
+    let wallet_2_id = Identity::Address(wallet_2_address);
+
+    let wallet_3_address: Address = wallet_3.clone().address().into();
+    let wallet_3_id = Identity::Address(wallet_3_address);
+
+    let provider = wallet_1.get_provider().unwrap();
+
+    let metadata = cid_to_metadata("bafybeib2skplfyirumj5fhxptybx7b7ryv3xntpu2oqn47pusykbzpalzq");
+
+    // only one-of-one projects can be auctioned
+    let _project1 = instance
+        .methods()
+        .list_project(0, 2, metadata.clone(), 0, [0; 3])
+        .call()
+        .await
+        .unwrap();
+
+    let _project2 = instance
+        .methods()
+        .list_project(0, 1, metadata.clone(), 0, [0; 3])
+        .call()
+        .await
+        .unwrap();
+
+    let commit_end_block = provider.latest_block_height().await.unwrap() + 10;
+    let reveal_end_block = commit_end_block + 10;
+
+    let not_one_of_one = instance
+        .methods()
+        .start_sealed_auction(0, commit_end_block, reveal_end_block)
+        .call()
+        .await;
+    assert!(not_one_of_one.is_err());
+
+    let _auction = instance
+        .methods()
+        .start_sealed_auction(1, commit_end_block, reveal_end_block)
+        .call()
+        .await
+        .unwrap();
+
+    // the auctioned project can't stop being one-of-one
+    let more_buyers = instance
+        .methods()
+        .update_project(1, 0, 0, metadata.clone(), None, 0, [0; 3])
+        .call()
+        .await;
+    assert!(more_buyers.is_err());
+
+    // the commitment is sha256 of the bid followed by the salt
+    let commit = |bid: u64, salt: [u8; 32]| -> Bits256 {
+        let commitment = Hasher::default()
+            .chain(bid.to_be_bytes())
+            .chain(salt)
+            .finalize();
+        Bits256(*commitment)
+    };
+
+    const BASE_ASSET_ID: AssetId = AssetId::new([0u8; 32]);
+
+    // wallet_2 bids 500 with a deposit of 800, wallet_3 bids 700
+    let call_params = CallParameters::new(Some(800), Some(BASE_ASSET_ID), None);
+    let _bid_2 = instance
+        .with_wallet(wallet_2.clone())
+        .unwrap()
+        .methods()
+        .commit_bid(1, commit(500, [2; 32]))
+        .call_params(call_params)
+        .call()
+        .await
+        .unwrap();
+
+    let call_params = CallParameters::new(Some(700), Some(BASE_ASSET_ID), None);
+    let _bid_3 = instance
+        .with_wallet(wallet_3.clone())
+        .unwrap()
+        .methods()
+        .commit_bid(1, commit(700, [3; 32]))
+        .call_params(call_params)
+        .call()
+        .await
+        .unwrap();
+
+    // the project can't be bought directly during the auction
+    let call_params = CallParameters::new(Some(1_000), Some(BASE_ASSET_ID), None);
+    let direct_buy = instance
+        .with_wallet(wallet_2.clone())
+        .unwrap()
+        .methods()
+        .buy_project(1)
+        .append_variable_outputs(2)
+        .call_params(call_params)
+        .call()
+        .await;
+    assert!(direct_buy.is_err());
+
+    // bids can't be revealed during the commit phase
+    let early_reveal = instance
+        .with_wallet(wallet_2.clone())
+        .unwrap()
+        .methods()
+        .reveal_bid(1, 500, Bits256([2; 32]))
+        .call()
+        .await;
+    assert!(early_reveal.is_err());
+
+    let block = provider.latest_block_height().await.unwrap();
+    provider.produce_blocks(commit_end_block - block, None).await.unwrap();
+
+    // the reveal has to match the commitment
+    let wrong_salt = instance
+        .with_wallet(wallet_2.clone())
+        .unwrap()
+        .methods()
+        .reveal_bid(1, 500, Bits256([9; 32]))
+        .call()
+        .await;
+    assert!(wrong_salt.is_err());
+
+    let _reveal_2 = instance
+        .with_wallet(wallet_2.clone())
+        .unwrap()
+        .methods()
+        .reveal_bid(1, 500, Bits256([2; 32]))
+        .call()
+        .await
+        .unwrap();
+
+    let _reveal_3 = instance
+        .with_wallet(wallet_3.clone())
+        .unwrap()
+        .methods()
+        .reveal_bid(1, 700, Bits256([3; 32]))
+        .call()
+        .await
+        .unwrap();
+
+    // the auction can't be settled during the reveal phase
+    let early_settle = instance
+        .methods()
+        .settle_sealed_auction(1)
+        .append_variable_outputs(1)
+        .call()
+        .await;
+    assert!(early_settle.is_err());
+
+    let block = provider.latest_block_height().await.unwrap();
+    provider.produce_blocks(reveal_end_block - block, None).await.unwrap();
+
+    let _settle = instance
+        .methods()
+        .settle_sealed_auction(1)
+        .append_variable_outputs(1)
+        .call()
+        .await
+        .unwrap();
+
+    let auction = instance.methods().get_sealed_auction(1).call().await.unwrap();
+    let auction = auction.value.unwrap();
+    assert!(auction.settled);
+    assert!(auction.highest_bid == 700);
+    assert!(auction.highest_bidder == Some(wallet_3_id.clone()));
+
+    let winner_has_project = instance
+        .methods()
+        .has_bought_project(1, wallet_3_id.clone())
+        .call()
+        .await
+        .unwrap();
+    assert!(winner_has_project.value == true);
+
+    // the sold one-of-one can still be updated for its buyer
+    let new_metadata = cid_to_metadata("bafybeih65sh4qmpkm7a3ib3o7romb23gqdlzvtqw4jgk2tnf6c66nqitci");
+    let _update = instance
+        .methods()
+        .update_project(1, 0, 1, new_metadata.clone(), None, 0, [0; 3])
+        .call()
+        .await
+        .unwrap();
+
+    let version_count = instance
+        .methods()
+        .get_metadata_version_count(1)
+        .call()
+        .await
+        .unwrap();
+    assert!(version_count.value == 2);
+
+    let project = instance.methods().get_project(1).call().await.unwrap();
+    assert!(project.value.metadata == new_metadata);
+
+    // everyone withdraws what's left of their deposit
+    let _withdraw_2 = instance
+        .with_wallet(wallet_2.clone())
+        .unwrap()
+        .methods()
+        .withdraw_bid(1)
+        .append_variable_outputs(1)
+        .call()
+        .await
+        .unwrap();
+
+    let _withdraw_3 = instance
+        .with_wallet(wallet_3.clone())
+        .unwrap()
+        .methods()
+        .withdraw_bid(1)
+        .append_variable_outputs(1)
+        .call()
+        .await
+        .unwrap();
+
+    let double_withdraw = instance
+        .with_wallet(wallet_2.clone())
+        .unwrap()
+        .methods()
+        .withdraw_bid(1)
+        .append_variable_outputs(1)
+        .call()
+        .await;
+    assert!(double_withdraw.is_err());
+
+    let bid_2 = instance
+        .methods()
+        .get_sealed_bid(1, wallet_2_id.clone())
+        .call()
+        .await
+        .unwrap();
+    assert!(bid_2.value.unwrap().revealed_bid == Some(500));
+
+    let balance_1: u64 = wallet_1.get_asset_balance(&BASE_ASSET_ID).await.unwrap();
+    let balance_2: u64 = wallet_2.get_asset_balance(&BASE_ASSET_ID).await.unwrap();
+    let balance_3: u64 = wallet_3.get_asset_balance(&BASE_ASSET_ID).await.unwrap();
+
+    assert!(balance_1 == 1000000700);
+    assert!(balance_2 == 1000000000);
+    assert!(balance_3 == 999999300);
+}
+
+#[tokio::test]
+async fn can_presell_project_with_funding_goal() {
+    let (instance, _id, wallets) = get_contract_instance().await;
+
+    let wallet_1 = wallets.get(0).unwrap();
+    let wallet_2 = wallets.get(1).unwrap();
+    let wallet_3 = wallets.get(2).unwrap();
+    let wallet_4 = wallets.get(3).unwrap();
+
+    let wallet_1_address: Address = wallet_1.clone().address().into();
+    let wallet_1_id = Identity::Address(wallet_1_address);
+
+    let wallet_2_address: Address = wallet_2.clone().address().into();
+    let wallet_2_id = Identity::Address(wallet_2_address);
+
+    let wallet_4_address: Address = wallet_4.clone().address().into();
+    let wallet_4_id = Identity::Address(wallet_4_address);
+
+    let provider = wallet_1.get_provider().unwrap();
+
+    let metadata = cid_to_metadata("bafybeib2skplfyirumj5fhxptybx7b7ryv3xntpu2oqn47pusykbzpalzq");
+
+    let _project1 = instance
+        .methods()
+        .list_project(500, 0, metadata.clone(), 0, [0; 3])
+        .call()
+        .await
+        .unwrap();
+
+    let _project2 = instance
+        .methods()
+        .list_project(500, 0, metadata.clone(), 0, [0; 3])
+        .call()
+        .await
+        .unwrap();
+
+    let deadline_block = provider.latest_block_height().await.unwrap() + 10;
+
+    // project 0 needs 2 buyers, project 1 needs 5000 in revenue
+    let no_goal = instance
+        .methods()
+        .start_presale(0, 0, 0, deadline_block)
+        .call()
+        .await;
+    assert!(no_goal.is_err());
+
+    let _presale1 = instance
+        .methods()
+        .start_presale(0, 2, 0, deadline_block)
+        .call()
+        .await
+        .unwrap();
+
+    let _presale2 = instance
+        .methods()
+        .start_presale(1, 0, 5000, deadline_block)
+        .call()
+        .await
+        .unwrap();
+
+    const BASE_ASSET_ID: AssetId = AssetId::new([0u8; 32]);
+
+    let call_params = CallParameters::new(Some(500), Some(BASE_ASSET_ID), None);
+    let _buy_1 = instance
+        .with_wallet(wallet_2.clone())
+        .unwrap()
+        .methods()
+        .buy_project(0)
+        .append_variable_outputs(1)
+        .call_params(call_params)
+        .call()
+        .await
+        .unwrap();
+
+    // no access until the goal is met
+    let has_project = instance
+        .methods()
+        .has_bought_project(0, wallet_2_id.clone())
+        .call()
+        .await
+        .unwrap();
+    assert!(has_project.value == false);
+
+    let early_release = instance
+        .methods()
+        .release_presale(0)
+        .append_variable_outputs(1)
+        .call()
+        .await;
+    assert!(early_release.is_err());
+
+    let call_params = CallParameters::new(Some(500), Some(BASE_ASSET_ID), None);
+    let _buy_2 = instance
+        .with_wallet(wallet_3.clone())
+        .unwrap()
+        .methods()
+        .buy_project(0)
+        .append_variable_outputs(1)
+        .call_params(call_params)
+        .call()
+        .await
+        .unwrap();
+
+    let has_project = instance
+        .methods()
+        .has_bought_project(0, wallet_2_id.clone())
+        .call()
+        .await
+        .unwrap();
+    assert!(has_project.value == true);
+
+    // escrowed payments aren't earnings until they're released
+    let creator_earnings = instance
+        .methods()
+        .get_creator_earnings(wallet_1_id.clone())
+        .call()
+        .await
+        .unwrap();
+    assert!(creator_earnings.value == 0);
+
+    let _release = instance
+        .methods()
+        .release_presale(0)
+        .append_variable_outputs(1)
+        .call()
+        .await
+        .unwrap();
+
+    let presale = instance.methods().get_presale(0).call().await.unwrap();
+    let presale = presale.value.unwrap();
+    assert!(presale.goal_met);
+    assert!(presale.released);
+    assert!(presale.raised == 1000);
+
+    let creator_earnings = instance
+        .methods()
+        .get_creator_earnings(wallet_1_id.clone())
+        .call()
+        .await
+        .unwrap();
+    assert!(creator_earnings.value == 1000);
+
+    // project 1 misses its goal, wallet_3 gifts it to wallet_4
+    let call_params = CallParameters::new(Some(500), Some(BASE_ASSET_ID), None);
+    let _buy_3 = instance
+        .with_wallet(wallet_2.clone())
+        .unwrap()
+        .methods()
+        .buy_project(1)
+        .append_variable_outputs(1)
+        .call_params(call_params)
+        .call()
+        .await
+        .unwrap();
+
+    let call_params = CallParameters::new(Some(500), Some(BASE_ASSET_ID), None);
+    let _gift = instance
+        .with_wallet(wallet_3.clone())
+        .unwrap()
+        .methods()
+        .buy_project_for(1, wallet_4_id.clone())
+        .append_variable_outputs(1)
+        .call_params(call_params)
+        .call()
+        .await
+        .unwrap();
+
+    let early_refund = instance
+        .with_wallet(wallet_2.clone())
+        .unwrap()
+        .methods()
+        .claim_presale_refund(1, wallet_2_id.clone())
+        .append_variable_outputs(1)
+        .call()
+        .await;
+    assert!(early_refund.is_err());
+
+    let block = provider.latest_block_height().await.unwrap();
+    provider.produce_blocks(deadline_block - block + 1, None).await.unwrap();
+
+    // the gift is refunded to the gifter, not the recipient
+    let recipient_refund = instance
+        .with_wallet(wallet_4.clone())
+        .unwrap()
+        .methods()
+        .claim_presale_refund(1, wallet_4_id.clone())
+        .append_variable_outputs(1)
+        .call()
+        .await;
+    assert!(recipient_refund.is_err());
+
+    let _gift_refund = instance
+        .with_wallet(wallet_3.clone())
+        .unwrap()
+        .methods()
+        .claim_presale_refund(1, wallet_4_id.clone())
+        .append_variable_outputs(1)
+        .call()
+        .await
+        .unwrap();
+
+    let _refund = instance
+        .with_wallet(wallet_2.clone())
+        .unwrap()
+        .methods()
+        .claim_presale_refund(1, wallet_2_id.clone())
+        .append_variable_outputs(1)
+        .call()
+        .await
+        .unwrap();
+
+    let double_refund = instance
+        .with_wallet(wallet_2.clone())
+        .unwrap()
+        .methods()
+        .claim_presale_refund(1, wallet_2_id.clone())
+        .append_variable_outputs(1)
+        .call()
+        .await;
+    assert!(double_refund.is_err());
+
+    // the refunds undo the purchases
+    let project = instance.methods().get_project(1).call().await.unwrap();
+    assert!(project.value.buyer_count == 0);
+    assert!(project.value.total_revenue == 0);
+
+    let presale = instance.methods().get_presale(1).call().await.unwrap();
+    assert!(presale.value.unwrap().raised == 0);
+
+    let has_project = instance
+        .methods()
+        .has_bought_project(1, wallet_4_id.clone())
+        .call()
+        .await
+        .unwrap();
+    assert!(has_project.value == false);
+
+    // after the failed pre-sale the project sells like a normal listing
+    let call_params = CallParameters::new(Some(500), Some(BASE_ASSET_ID), None);
+    let _rebuy = instance
+        .with_wallet(wallet_2.clone())
+        .unwrap()
+        .methods()
+        .buy_project(1)
+        .append_variable_outputs(1)
+        .call_params(call_params)
+        .call()
+        .await
+        .unwrap();
+
+    let has_project = instance
+        .methods()
+        .has_bought_project(1, wallet_2_id.clone())
+        .call()
+        .await
+        .unwrap();
+    assert!(has_project.value == true);
+
+    let stats = instance.methods().get_marketplace_stats().call().await.unwrap();
+    assert!(stats.value.purchase_count == 3);
+    // wallet_4 only had the refunded gift, so it isn't counted as a buyer
+    assert!(stats.value.buyer_count == 2);
+
+    // the refunded pre-sale payments never counted as earnings
+    let creator_earnings = instance
+        .methods()
+        .get_creator_earnings(wallet_1_id.clone())
+        .call()
+        .await
+        .unwrap();
+    assert!(creator_earnings.value == 1500);
+
+    let balance_1: u64 = wallet_1.get_asset_balance(&BASE_ASSET_ID).await.unwrap();
+    let balance_2: u64 = wallet_2.get_asset_balance(&BASE_ASSET_ID).await.unwrap();
+    let balance_3: u64 = wallet_3.get_asset_balance(&BASE_ASSET_ID).await.unwrap();
+    let balance_4: u64 = wallet_4.get_asset_balance(&BASE_ASSET_ID).await.unwrap();
+
+    assert!(balance_1 == 1000001500);
+    assert!(balance_2 == 999999000);
+    assert!(balance_3 == 999999500);
+    assert!(balance_4 == 1000000000);
+}
+
+#[tokio::test]
+async fn can_tip_creator() {
+    let (instance, _id, wallets) = get_contract_instance().await;
+
+    let wallet_1 = wallets.get(0).unwrap();
+    let wallet_2 = wallets.get(1).unwrap();
+    let wallet_3 = wallets.get(2).unwrap();
+
+    let _initialize_resp = instance.methods().initialize_owner().call().await.unwrap();
+
+    let metadata = cid_to_metadata("bafybeib2skplfyirumj5fhxptybx7b7ryv3xntpu2oqn47pusykbzpalzq");
+
+    let _project = instance
+        .methods()
+        .list_project(0, 0, metadata.clone(), 0, [0; 3])
+        .call()
+        .await
+        .unwrap();
+
+    const BASE_ASSET_ID: AssetId = AssetId::new([0u8; 32]);
+
+    // tips are sent to the creator in full by default
+    let call_params = CallParameters::new(Some(2_000), Some(BASE_ASSET_ID), None);
+    let _tip_1 = instance
+        .with_wallet(wallet_2.clone())
+        .unwrap()
+        .methods()
+        .tip_creator(0)
+        .append_variable_outputs(1)
+        .call_params(call_params)
+        .call()
+        .await
+        .unwrap();
+
+    let call_params = CallParameters::new(Some(0), Some(BASE_ASSET_ID), None);
+    let empty_tip = instance
+        .with_wallet(wallet_2.clone())
+        .unwrap()
+        .methods()
+        .tip_creator(0)
+        .append_variable_outputs(1)
+        .call_params(call_params)
+        .call()
+        .await;
+    assert!(empty_tip.is_err());
+
+    // only the contract owner can turn on commission for tips
+    let not_owner = instance
+        .with_wallet(wallet_2.clone())
+        .unwrap()
+        .methods()
+        .set_tip_commission(true)
+        .call()
+        .await;
+    assert!(not_owner.is_err());
+
+    let _commission = instance
+        .methods()
+        .set_tip_commission(true)
+        .call()
+        .await
+        .unwrap();
+
+    let call_params = CallParameters::new(Some(2_000), Some(BASE_ASSET_ID), None);
+    let _tip_2 = instance
+        .with_wallet(wallet_3.clone())
+        .unwrap()
+        .methods()
+        .tip_creator(0)
+        .append_variable_outputs(1)
+        .call_params(call_params)
+        .call()
+        .await
+        .unwrap();
+
+    let tips = instance.methods().get_project_tips(0).call().await.unwrap();
+    assert!(tips.value == 4_000);
+
+    let balance_1: u64 = wallet_1.get_asset_balance(&BASE_ASSET_ID).await.unwrap();
+    let balance_2: u64 = wallet_2.get_asset_balance(&BASE_ASSET_ID).await.unwrap();
+    let balance_3: u64 = wallet_3.get_asset_balance(&BASE_ASSET_ID).await.unwrap();
+
+    assert!(balance_1 == 1000003900);
+    assert!(balance_2 == 999998000);
+    assert!(balance_3 == 999998000);
+}
+
+#[tokio::test]
+async fn can_sell_projects_in_a_bundle() {
+    let (instance, _id, wallets) = get_contract_instance().await;
+
+    let wallet_1 = wallets.get(0).unwrap();
+    let wallet_2 = wallets.get(1).unwrap();
+    let wallet_3 = wallets.get(2).unwrap();
+
+    let wallet_2_address: Address = wallet_2.clone().address().into();
+    let wallet_2_id = Identity::Address(wallet_2_address);
+
+    let wallet_3_address: Address = wallet_3.clone().address().into();
+    let wallet_3_id = Identity::Address(wallet_3_address);
+
+    let metadata = cid_to_metadata("bafybeib2skplfyirumj5fhxptybx7b7ryv3xntpu2oqn47pusykbzpalzq");
+
+    for _ in 0..3 {
+        let _project = instance
+            .methods()
+            .list_project(500, 0, metadata.clone(), 0, [0; 3])
+            .call()
+            .await
+            .unwrap();
+    }
+
+    let _project4 = instance
+        .with_wallet(wallet_2.clone())
+        .unwrap()
+        .methods()
+        .list_project(500, 0, metadata.clone(), 0, [0; 3])
+        .call()
+        .await
+        .unwrap();
+
+    // creators can only bundle their own projects
+    let not_owner = instance
+        .methods()
+        .create_bundle(vec![0, 3], 800)
+        .call()
+        .await;
+    assert!(not_owner.is_err());
+
+    let too_small = instance.methods().create_bundle(vec![0], 400).call().await;
+    assert!(too_small.is_err());
+
+    let duplicate = instance.methods().create_bundle(vec![0, 0], 400).call().await;
+    assert!(duplicate.is_err());
+
+    let bundle = instance
+        .methods()
+        .create_bundle(vec![0, 1, 2], 900)
+        .call()
+        .await
+        .unwrap();
+    assert!(bundle.value.bundle_id == 0);
+    assert!(bundle.value.projects.current_ix == 3);
+
+    const BASE_ASSET_ID: AssetId = AssetId::new([0u8; 32]);
+
+    // wallet_2 already owns project 0, so its share of the bundle isn't charged
+    let call_params = CallParameters::new(Some(500), Some(BASE_ASSET_ID), None);
+    let _buy = instance
+        .with_wallet(wallet_2.clone())
+        .unwrap()
+        .methods()
+        .buy_project(0)
+        .append_variable_outputs(1)
+        .call_params(call_params)
+        .call()
+        .await
+        .unwrap();
+
+    let call_params = CallParameters::new(Some(900), Some(BASE_ASSET_ID), None);
+    let _bundle_2 = instance
+        .with_wallet(wallet_2.clone())
+        .unwrap()
+        .methods()
+        .buy_bundle(0)
+        .append_variable_outputs(3)
+        .call_params(call_params)
+        .call()
+        .await
+        .unwrap();
+
+    let call_params = CallParameters::new(Some(900), Some(BASE_ASSET_ID), None);
+    let _bundle_3 = instance
+        .with_wallet(wallet_3.clone())
+        .unwrap()
+        .methods()
+        .buy_bundle(0)
+        .append_variable_outputs(3)
+        .call_params(call_params)
+        .call()
+        .await
+        .unwrap();
+
+    // the bundle can't be bought again once every project is owned
+    let call_params = CallParameters::new(Some(900), Some(BASE_ASSET_ID), None);
+    let rebuy = instance
+        .with_wallet(wallet_3.clone())
+        .unwrap()
+        .methods()
+        .buy_bundle(0)
+        .append_variable_outputs(1)
+        .call_params(call_params)
+        .call()
+        .await;
+    assert!(rebuy.is_err());
+
+    let project = instance.methods().get_project(0).call().await.unwrap();
+    assert!(project.value.buyer_count == 2);
+
+    let has_project_2 = instance
+        .methods()
+        .has_bought_project(2, wallet_2_id.clone())
+        .call()
+        .await
+        .unwrap();
+    assert!(has_project_2.value == true);
+
+    let has_project_3 = instance
+        .methods()
+        .has_bought_project(1, wallet_3_id.clone())
+        .call()
+        .await
+        .unwrap();
+    assert!(has_project_3.value == true);
+
+    let balance_1: u64 = wallet_1.get_asset_balance(&BASE_ASSET_ID).await.unwrap();
+    let balance_2: u64 = wallet_2.get_asset_balance(&BASE_ASSET_ID).await.unwrap();
+    let balance_3: u64 = wallet_3.get_asset_balance(&BASE_ASSET_ID).await.unwrap();
+
+    assert!(balance_1 == 1000002000);
+    assert!(balance_2 == 999998900);
+    assert!(balance_3 == 999999100);
+}
+
+#[tokio::test]
+async fn can_moderate_projects_and_reviews() {
+    let (instance, _id, wallets) = get_contract_instance().await;
+
+    let wallet_2 = wallets.get(1).unwrap();
+    let wallet_3 = wallets.get(2).unwrap();
+
+    let _initialize_resp = instance.methods().initialize_owner().call().await.unwrap();
+
+    let metadata = cid_to_metadata("bafybeib2skplfyirumj5fhxptybx7b7ryv3xntpu2oqn47pusykbzpalzq");
+
+    for _ in 0..2 {
+        let _project = instance
+            .methods()
+            .list_project(20, 0, metadata.clone(), 0, [0; 3])
+            .call()
+            .await
+            .unwrap();
+    }
+
+    const BASE_ASSET_ID: AssetId = AssetId::new([0u8; 32]);
+
+    let mut review_ids = vec![];
+    for wallet in [wallet_2, wallet_3] {
+        let call_params = CallParameters::new(Some(20), Some(BASE_ASSET_ID), None);
+        let _buy = instance
+            .with_wallet(wallet.clone())
+            .unwrap()
+            .methods()
+            .buy_project(0)
+            .append_variable_outputs(1)
+            .call_params(call_params)
+            .call()
+            .await
+            .unwrap();
+
+        let review = instance
+            .with_wallet(wallet.clone())
+            .unwrap()
+            .methods()
+            .review_project(0, 1)
+            .call()
+            .await
+            .unwrap();
+        review_ids.push(review.value);
+    }
+
+    // only the contract owner can moderate
+    let not_owner = instance
+        .with_wallet(wallet_2.clone())
+        .unwrap()
+        .methods()
+        .hide_project(1, 3)
+        .call()
+        .await;
+    assert!(not_owner.is_err());
+
+    let _hide = instance.methods().hide_project(1, 3).call().await.unwrap();
+
+    let reason = instance.methods().get_hidden_reason(1).call().await.unwrap();
+    assert!(reason.value == Some(3));
+
+    // hidden projects can't be bought
+    let call_params = CallParameters::new(Some(20), Some(BASE_ASSET_ID), None);
+    let hidden_buy = instance
+        .with_wallet(wallet_2.clone())
+        .unwrap()
+        .methods()
+        .buy_project(1)
+        .append_variable_outputs(1)
+        .call_params(call_params)
+        .call()
+        .await;
+    assert!(hidden_buy.is_err());
+
+    // hidden projects can't be tipped either
+    let call_params = CallParameters::new(Some(20), Some(BASE_ASSET_ID), None);
+    let hidden_tip = instance
+        .with_wallet(wallet_2.clone())
+        .unwrap()
+        .methods()
+        .tip_creator(1)
+        .append_variable_outputs(1)
+        .call_params(call_params)
+        .call()
+        .await;
+    assert!(hidden_tip.is_err());
+
+    // a hidden project can be bought again once it's unhidden
+    let not_owner = instance
+        .with_wallet(wallet_2.clone())
+        .unwrap()
+        .methods()
+        .unhide_project(1)
+        .call()
+        .await;
+    assert!(not_owner.is_err());
+
+    let _unhide = instance.methods().unhide_project(1).call().await.unwrap();
+
+    let reason = instance.methods().get_hidden_reason(1).call().await.unwrap();
+    assert!(reason.value == None);
+
+    let double_unhide = instance.methods().unhide_project(1).call().await;
+    assert!(double_unhide.is_err());
+
+    let call_params = CallParameters::new(Some(20), Some(BASE_ASSET_ID), None);
+    let _buy = instance
+        .with_wallet(wallet_2.clone())
+        .unwrap()
+        .methods()
+        .buy_project(1)
+        .append_variable_outputs(1)
+        .call_params(call_params)
+        .call()
+        .await
+        .unwrap();
+
+    let not_owner = instance
+        .with_wallet(wallet_2.clone())
+        .unwrap()
+        .methods()
+        .remove_review(review_ids[0])
+        .call()
+        .await;
+    assert!(not_owner.is_err());
+
+    // reviews are removed by the id review_project returned
+    let _remove = instance
+        .methods()
+        .remove_review(review_ids[0])
+        .call()
+        .await
+        .unwrap();
+
+    let double_remove = instance.methods().remove_review(review_ids[0]).call().await;
+    assert!(double_remove.is_err());
+
+    let no_review = instance.methods().remove_review(0).call().await;
+    assert!(no_review.is_err());
+
+    // the removed review can't be read, wallet_3's still can
+    let removed_rating = instance.methods().get_project_rating(0).call().await;
+    assert!(removed_rating.is_err());
+
+    let rating = instance.methods().get_project_rating(1).call().await.unwrap();
+    assert!(rating.value.1 == 1);
+
+    // only wallet_3's review is left
+    let ratings = instance
+        .methods()
+        .get_project_ratings_ix(0)
+        .call()
+        .await
+        .unwrap();
+    assert!(ratings.value.current_ix == 1);
+    assert!(ratings.value.inner[0] == 2);
+
+    let stats = instance.methods().get_marketplace_stats().call().await.unwrap();
+    assert!(stats.value.review_count == 1);
+}
+
+#[tokio::test]
+async fn can_block_identities() {
+    let (instance, _id, wallets) = get_contract_instance().await;
+
+    let wallet_2 = wallets.get(1).unwrap();
+    let wallet_3 = wallets.get(2).unwrap();
+    let wallet_4 = wallets.get(3).unwrap();
+
+    let wallet_2_address: Address = wallet_2.clone().address().into();
+    let wallet_2_id = Identity::Address(wallet_2_address);
+
+    let wallet_3_address: Address = wallet_3.clone().address().into();
+    let wallet_3_id = Identity::Address(wallet_3_address);
+
+    let _initialize_resp = instance.methods().initialize_owner().call().await.unwrap();
+
+    let metadata = cid_to_metadata("bafybeib2skplfyirumj5fhxptybx7b7ryv3xntpu2oqn47pusykbzpalzq");
+
+    for _ in 0..2 {
+        let _project = instance
+            .methods()
+            .list_project(20, 0, metadata.clone(), 0, [0; 3])
+            .call()
+            .await
+            .unwrap();
+    }
+
+    const BASE_ASSET_ID: AssetId = AssetId::new([0u8; 32]);
+
+    // wallet_2 buys project 0 before it's blocked
+    let call_params = CallParameters::new(Some(20), Some(BASE_ASSET_ID), None);
+    let _buy = instance
+        .with_wallet(wallet_2.clone())
+        .unwrap()
+        .methods()
+        .buy_project(0)
+        .append_variable_outputs(1)
+        .call_params(call_params)
+        .call()
+        .await
+        .unwrap();
+
+    // only the contract owner can block
+    let not_owner = instance
+        .with_wallet(wallet_3.clone())
+        .unwrap()
+        .methods()
+        .block_identity(wallet_2_id.clone())
+        .call()
+        .await;
+    assert!(not_owner.is_err());
+
+    let _block = instance
+        .methods()
+        .block_identity(wallet_2_id.clone())
+        .call()
+        .await
+        .unwrap();
+
+    let blocked = instance
+        .methods()
+        .is_blocked(wallet_2_id.clone())
+        .call()
+        .await
+        .unwrap();
+    assert!(blocked.value == true);
+
+    let list = instance
+        .with_wallet(wallet_2.clone())
+        .unwrap()
+        .methods()
+        .list_project(20, 0, metadata.clone(), 0, [0; 3])
+        .call()
+        .await;
+    assert!(list.is_err());
+
+    let call_params = CallParameters::new(Some(20), Some(BASE_ASSET_ID), None);
+    let buy = instance
+        .with_wallet(wallet_2.clone())
+        .unwrap()
+        .methods()
+        .buy_project(1)
+        .append_variable_outputs(1)
+        .call_params(call_params)
+        .call()
+        .await;
+    assert!(buy.is_err());
+
+    let review = instance
+        .with_wallet(wallet_2.clone())
+        .unwrap()
+        .methods()
+        .review_project(0, 1)
+        .call()
+        .await;
+    assert!(review.is_err());
+
+    // projects bought before the block stay bought
+    let has_project = instance
+        .methods()
+        .has_bought_project(0, wallet_2_id.clone())
+        .call()
+        .await
+        .unwrap();
+    assert!(has_project.value == true);
+
+    let _unblock = instance
+        .methods()
+        .unblock_identity(wallet_2_id.clone())
+        .call()
+        .await
+        .unwrap();
+
+    let _review = instance
+        .with_wallet(wallet_2.clone())
+        .unwrap()
+        .methods()
+        .review_project(0, 1)
+        .call()
+        .await
+        .unwrap();
+
+    let blocked = instance
+        .methods()
+        .is_blocked(wallet_2_id.clone())
+        .call()
+        .await
+        .unwrap();
+    assert!(blocked.value == false);
+
+    // wallet_3 lists project 2 and is then blocked as a creator
+    let _project3 = instance
+        .with_wallet(wallet_3.clone())
+        .unwrap()
+        .methods()
+        .list_project(20, 0, metadata.clone(), 0, [0; 3])
+        .call()
+        .await
+        .unwrap();
+
+    let _block_creator = instance
+        .methods()
+        .block_identity(wallet_3_id.clone())
+        .call()
+        .await
+        .unwrap();
+
+    let call_params = CallParameters::new(Some(20), Some(BASE_ASSET_ID), None);
+    let creator_buy = instance
+        .with_wallet(wallet_4.clone())
+        .unwrap()
+        .methods()
+        .buy_project(2)
+        .append_variable_outputs(1)
+        .call_params(call_params)
+        .call()
+        .await;
+    assert!(creator_buy.is_err());
+
+    let call_params = CallParameters::new(Some(20), Some(BASE_ASSET_ID), None);
+    let creator_tip = instance
+        .with_wallet(wallet_4.clone())
+        .unwrap()
+        .methods()
+        .tip_creator(2)
+        .append_variable_outputs(1)
+        .call_params(call_params)
+        .call()
+        .await;
+    assert!(creator_tip.is_err());
+
+    let creator_update = instance
+        .with_wallet(wallet_3.clone())
+        .unwrap()
+        .methods()
+        .update_project(2, 10, 0, metadata.clone(), None, 0, [0; 3])
+        .call()
+        .await;
+    assert!(creator_update.is_err());
+
+    // a blocked referrer can't earn referral fees
+    let call_params = CallParameters::new(Some(20), Some(BASE_ASSET_ID), None);
+    let blocked_referral = instance
+        .with_wallet(wallet_4.clone())
+        .unwrap()
+        .methods()
+        .buy_project_with_referrer(1, wallet_3_id.clone())
+        .append_variable_outputs(2)
+        .call_params(call_params)
+        .call()
+        .await;
+    assert!(blocked_referral.is_err());
+}
+
+#[tokio::test]
+async fn can_take_commission_on_whole_bundle() {
+    let (instance, _id, wallets) = get_contract_instance().await;
+
+    let wallet_1 = wallets.get(0).unwrap();
+    let wallet_2 = wallets.get(1).unwrap();
+
+    let metadata = cid_to_metadata("bafybeib2skplfyirumj5fhxptybx7b7ryv3xntpu2oqn47pusykbzpalzq");
+
+    for _ in 0..2 {
+        let _project = instance
+            .methods()
+            .list_project(1_500, 0, metadata.clone(), 0, [0; 3])
+            .call()
+            .await
+            .unwrap();
+    }
+
+    // each share is under the 1_000 commission threshold, the bundle isn't
+    let _bundle = instance
+        .methods()
+        .create_bundle(vec![0, 1], 2_000)
+        .call()
+        .await
+        .unwrap();
+
+    const BASE_ASSET_ID: AssetId = AssetId::new([0u8; 32]);
+
+    let call_params = CallParameters::new(Some(2_000), Some(BASE_ASSET_ID), None);
+    let _buy = instance
+        .with_wallet(wallet_2.clone())
+        .unwrap()
+        .methods()
+        .buy_bundle(0)
+        .append_variable_outputs(2)
+        .call_params(call_params)
+        .call()
+        .await
+        .unwrap();
+
+    let project1 = instance.methods().get_project(0).call().await.unwrap();
+    let project2 = instance.methods().get_project(1).call().await.unwrap();
+    assert!(project1.value.total_revenue == 1_000);
+    assert!(project1.value.total_commission == 50);
+    assert!(project2.value.total_commission == 50);
+
+    let balance_1: u64 = wallet_1.get_asset_balance(&BASE_ASSET_ID).await.unwrap();
+    let balance_2: u64 = wallet_2.get_asset_balance(&BASE_ASSET_ID).await.unwrap();
+
+    assert!(balance_1 == 1000001900);
+    assert!(balance_2 == 999998000);
+}
+
+#[tokio::test]
+async fn rejects_sealed_bids_below_reserve() {
+    let (instance, _id, wallets) = get_contract_instance().await;
+
+    let wallet_1 = wallets.get(0).unwrap();
+    let wallet_2 = wallets.get(1).unwrap();
+
+    let wallet_2_address: Address = wallet_2.clone().address().into();
+    let wallet_2_id = Identity::Address(wallet_2_address);
+
+    let provider = wallet_1.get_provider().unwrap();
+
+    let metadata = cid_to_metadata("bafybeib2skplfyirumj5fhxptybx7b7ryv3xntpu2oqn47pusykbzpalzq");
+
+    // the project's price is the auction's reserve
+    let _project = instance
+        .methods()
+        .list_project(1_000, 1, metadata.clone(), 0, [0; 3])
+        .call()
+        .await
+        .unwrap();
+
+    let commit_end_block = provider.latest_block_height().await.unwrap() + 10;
+    let reveal_end_block = commit_end_block + 10;
+
+    let _auction = instance
+        .methods()
+        .start_sealed_auction(0, commit_end_block, reveal_end_block)
+        .call()
+        .await
+        .unwrap();
+
+    let auction = instance.methods().get_sealed_auction(0).call().await.unwrap();
+    assert!(auction.value.unwrap().reserve_price == 1_000);
+
+    let commitment = Hasher::default()
+        .chain(500u64.to_be_bytes())
+        .chain([2; 32])
+        .finalize();
+
+    const BASE_ASSET_ID: AssetId = AssetId::new([0u8; 32]);
+
+    let call_params = CallParameters::new(Some(500), Some(BASE_ASSET_ID), None);
+    let _bid = instance
+        .with_wallet(wallet_2.clone())
+        .unwrap()
+        .methods()
+        .commit_bid(0, Bits256(*commitment))
+        .call_params(call_params)
+        .call()
+        .await
+        .unwrap();
+
+    let block = provider.latest_block_height().await.unwrap();
+    provider.produce_blocks(commit_end_block - block, None).await.unwrap();
+
+    let _reveal = instance
+        .with_wallet(wallet_2.clone())
+        .unwrap()
+        .methods()
+        .reveal_bid(0, 500, Bits256([2; 32]))
+        .call()
+        .await
+        .unwrap();
+
+    let block = provider.latest_block_height().await.unwrap();
+    provider.produce_blocks(reveal_end_block - block, None).await.unwrap();
+
+    let _settle = instance
+        .methods()
+        .settle_sealed_auction(0)
+        .append_variable_outputs(1)
+        .call()
+        .await
+        .unwrap();
+
+    // the only bid was under the reserve, so the project stays unsold
+    let auction = instance.methods().get_sealed_auction(0).call().await.unwrap();
+    let auction = auction.value.unwrap();
+    assert!(auction.settled);
+    assert!(auction.highest_bidder == None);
+
+    let has_project = instance
+        .methods()
+        .has_bought_project(0, wallet_2_id.clone())
+        .call()
+        .await
+        .unwrap();
+    assert!(has_project.value == false);
+
+    let _withdraw = instance
+        .with_wallet(wallet_2.clone())
+        .unwrap()
+        .methods()
+        .withdraw_bid(0)
+        .append_variable_outputs(1)
+        .call()
+        .await
+        .unwrap();
+
+    let balance_2: u64 = wallet_2.get_asset_balance(&BASE_ASSET_ID).await.unwrap();
+    assert!(balance_2 == 1000000000);
+}
@@ -0,0 +1,183 @@
+use crate::{Codec, Metadata};
+use fuels::core::types::Bits256;
+
+// multicodec code of a sha2-256 multihash
+const SHA2_256: u64 = 0x12;
+
+const BASE32_ALPHABET: &[u8] = b"abcdefghijklmnopqrstuvwxyz234567";
+const BASE58_ALPHABET: &[u8] = b"123456789ABCDEFGHJKLMNPQRSTUVWXYZabcdefghijkmnopqrstuvwxyz";
+const BASE64URL_ALPHABET: &[u8] =
+    b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789-_";
+
+// convert an IPFS CID (v0, or v1 in base32 or base58btc) to the on-chain Metadata
+pub fn cid_to_metadata(cid: &str) -> Metadata {
+    if cid.len() == 46 && cid.starts_with("Qm") {
+        let bytes = decode_base58(cid);
+        let digest = read_sha2_256(&bytes);
+        return Metadata {
+            codec: Codec::CidV0(),
+            digest: Bits256(digest),
+        };
+    }
+
+    let bytes = match cid.chars().next() {
+        Some('b') => decode_bits(&cid[1..], BASE32_ALPHABET, 5),
+        Some('z') => decode_base58(&cid[1..]),
+        _ => panic!("unsupported multibase prefix in CID {}", cid),
+    };
+    let (version, rest) = read_varint(&bytes);
+    assert!(version == 1, "unsupported CID version {}", version);
+    let (content_codec, rest) = read_varint(rest);
+    let digest = read_sha2_256(rest);
+
+    Metadata {
+        codec: Codec::CidV1(content_codec),
+        digest: Bits256(digest),
+    }
+}
+
+// convert an Arweave transaction id to the on-chain Metadata
+pub fn arweave_id_to_metadata(id: &str) -> Metadata {
+    let bytes = decode_bits(id, BASE64URL_ALPHABET, 6);
+    let digest: [u8; 32] = bytes
+        .try_into()
+        .expect("Arweave ids are 32 bytes");
+
+    Metadata {
+        codec: Codec::Arweave(),
+        digest: Bits256(digest),
+    }
+}
+
+// convert the on-chain Metadata back to a CID (v1 in base32) or Arweave id
+pub fn metadata_to_string(metadata: &Metadata) -> String {
+    let digest = metadata.digest.0;
+    match metadata.codec {
+        Codec::CidV0() => {
+            let mut bytes = vec![SHA2_256 as u8, 32];
+            bytes.extend_from_slice(&digest);
+            encode_base58(&bytes)
+        }
+        Codec::CidV1(content_codec) => {
+            let mut bytes = vec![];
+            write_varint(1, &mut bytes);
+            write_varint(content_codec, &mut bytes);
+            write_varint(SHA2_256, &mut bytes);
+            write_varint(32, &mut bytes);
+            bytes.extend_from_slice(&digest);
+            format!("b{}", encode_bits(&bytes, BASE32_ALPHABET, 5))
+        }
+        Codec::Arweave() => encode_bits(&digest, BASE64URL_ALPHABET, 6),
+    }
+}
+
+fn read_sha2_256(bytes: &[u8]) -> [u8; 32] {
+    let (hash_fn, rest) = read_varint(bytes);
+    assert!(hash_fn == SHA2_256, "only sha2-256 CIDs are supported");
+    let (len, rest) = read_varint(rest);
+    assert!(len == 32 && rest.len() == 32, "invalid sha2-256 digest");
+    rest.try_into().unwrap()
+}
+
+fn read_varint(bytes: &[u8]) -> (u64, &[u8]) {
+    let mut value = 0;
+    for (i, byte) in bytes.iter().enumerate() {
+        value |= ((byte & 0x7f) as u64) << (7 * i);
+        if byte & 0x80 == 0 {
+            return (value, &bytes[i + 1..]);
+        }
+    }
+    panic!("unterminated varint");
+}
+
+fn write_varint(mut value: u64, out: &mut Vec<u8>) {
+    while value >= 0x80 {
+        out.push((value as u8 & 0x7f) | 0x80);
+        value >>= 7;
+    }
+    out.push(value as u8);
+}
+
+// decode an unpadded base32 or base64url string
+fn decode_bits(input: &str, alphabet: &[u8], bits_per_char: u32) -> Vec<u8> {
+    let mut out = vec![];
+    let mut buffer: u32 = 0;
+    let mut bits = 0;
+    for c in input.bytes() {
+        let value = alphabet
+            .iter()
+            .position(|&a| a == c)
+            .unwrap_or_else(|| panic!("invalid character {}", c as char));
+        buffer = (buffer << bits_per_char) | value as u32;
+        bits += bits_per_char;
+        if bits >= 8 {
+            bits -= 8;
+            out.push((buffer >> bits) as u8);
+            buffer &= (1 << bits) - 1;
+        }
+    }
+    out
+}
+
+// encode bytes as an unpadded base32 or base64url string
+fn encode_bits(input: &[u8], alphabet: &[u8], bits_per_char: u32) -> String {
+    let mut out = String::new();
+    let mut buffer: u32 = 0;
+    let mut bits = 0;
+    for byte in input {
+        buffer = (buffer << 8) | *byte as u32;
+        bits += 8;
+        while bits >= bits_per_char {
+            bits -= bits_per_char;
+            out.push(alphabet[(buffer >> bits) as usize & ((1 << bits_per_char) - 1)] as char);
+        }
+        buffer &= (1 << bits) - 1;
+    }
+    if bits > 0 {
+        out.push(alphabet[(buffer << (bits_per_char - bits)) as usize] as char);
+    }
+    out
+}
+
+fn decode_base58(input: &str) -> Vec<u8> {
+    let mut out: Vec<u8> = vec![];
+    for c in input.bytes() {
+        let mut carry = BASE58_ALPHABET
+            .iter()
+            .position(|&a| a == c)
+            .unwrap_or_else(|| panic!("invalid character {}", c as char)) as u32;
+        for byte in out.iter_mut().rev() {
+            carry += *byte as u32 * 58;
+            *byte = carry as u8;
+            carry >>= 8;
+        }
+        while carry > 0 {
+            out.insert(0, carry as u8);
+            carry >>= 8;
+        }
+    }
+    let zeros = input.bytes().take_while(|&c| c == b'1').count();
+    let mut bytes = vec![0; zeros];
+    bytes.extend(out);
+    bytes
+}
+
+fn encode_base58(input: &[u8]) -> String {
+    let mut digits: Vec<u8> = vec![];
+    for byte in input {
+        let mut carry = *byte as u32;
+        for digit in digits.iter_mut().rev() {
+            carry += *digit as u32 * 256;
+            *digit = (carry % 58) as u8;
+            carry /= 58;
+        }
+        while carry > 0 {
+            digits.insert(0, (carry % 58) as u8);
+            carry /= 58;
+        }
+    }
+    let zeros = input.iter().take_while(|&&b| b == 0).count();
+    let mut out = "1".repeat(zeros);
+    out.extend(digits.iter().map(|&d| BASE58_ALPHABET[d as usize] as char));
+    out
+}